@@ -49,8 +49,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(elves.sum())
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_01;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(1, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 1);
+    advent_of_code::fingerprint::check(1, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -59,6 +63,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 1);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(1, part_one, part_two);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 1);
@@ -120,8 +120,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(score)
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_02;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(2, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 2);
+    advent_of_code::fingerprint::check(2, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -130,6 +134,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 2);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(2, part_one, part_two);
+    }
+
     #[test]
     fn test_shape_score() {
         assert_eq!(Shape::Rock.score(), 1);
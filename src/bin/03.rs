@@ -96,8 +96,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(badges.iter().sum())
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_03;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(3, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 3);
+    advent_of_code::fingerprint::check(3, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -106,6 +110,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 3);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(3, part_one, part_two);
+    }
+
     #[test]
     fn test_char_to_value() {
         assert_eq!('a'.to_value(), 1);
@@ -1,48 +1,50 @@
-extern crate gcollections;
-extern crate interval;
+use advent_of_code::helpers::{
+    interval::Interval,
+    parse::{lines, parse_all, unsigned},
+};
+use nom::{character::complete::char, combinator::map, sequence::separated_pair, IResult};
 
-use crate::interval::Interval;
-use crate::interval::ops::*;
-use gcollections::ops::*;
+type Pair = (Interval<u32>, Interval<u32>);
+
+fn parse_interval(input: &str) -> IResult<&str, Interval<u32>> {
+    map(
+        separated_pair(unsigned, char('-'), unsigned),
+        |(start, end)| Interval::new(start, end),
+    )(input)
+}
+
+fn parse_input(input: &str) -> Vec<Pair> {
+    parse_all(
+        lines(separated_pair(parse_interval, char(','), parse_interval)),
+        input,
+    )
+    .unwrap_or_else(|e| panic!("{}", e))
+}
 
 pub fn part_one(input: &str) -> Option<u32> {
-    Some(input
-        .lines()
-        .map(|line| {
-            let mut intervals = line
-            .split(',')
-            .map(|interval| {
-                let (start, end) = interval.split_at(interval.find('-').unwrap());
-                Interval::new(start.parse::<u32>().unwrap(), end[1..].parse::<u32>().unwrap())
-            });
-            let interval_a = intervals.next().unwrap();
-            let interval_b = intervals.next().unwrap();
-            interval_a.is_subset(&interval_b) || interval_b.is_subset(&interval_a)
-        })
-        .filter(|&x| x)
-        .count() as u32)
+    Some(
+        parse_input(input)
+            .iter()
+            .filter(|(a, b)| a.covers(b) || b.covers(a))
+            .count() as u32,
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    Some(input
-        .lines()
-        .map(|line| {
-            let mut intervals = line
-            .split(',')
-            .map(|interval| {
-                let (start, end) = interval.split_at(interval.find('-').unwrap());
-                Interval::new(start.parse::<u32>().unwrap(), end[1..].parse::<u32>().unwrap())
-            });
-            let interval_a = intervals.next().unwrap();
-            let interval_b = intervals.next().unwrap();
-            interval_a.overlap(&interval_b)
-        })
-        .filter(|&x| x)
-        .count() as u32)
+    Some(
+        parse_input(input)
+            .iter()
+            .filter(|(a, b)| a.overlaps(b))
+            .count() as u32,
+    )
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_04;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(4, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 4);
+    advent_of_code::fingerprint::check(4, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -51,6 +53,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 4);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(4, part_one, part_two);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 4);
@@ -1,76 +1,386 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+enum ParseError {
+    MissingSteps,
+    MissingLabels,
+    InvalidCrate { line: usize, column: usize },
+    FloatingCrate { line: usize, column: usize },
+    InvalidStep { line: usize, found: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingSteps => write!(f, "expected an empty line before the steps"),
+            ParseError::MissingLabels => write!(f, "expected a line of stack numbers"),
+            ParseError::InvalidCrate { line, column } => {
+                write!(
+                    f,
+                    "line {}, column {}: expected a crate like [A]",
+                    line, column
+                )
+            }
+            ParseError::FloatingCrate { line, column } => {
+                write!(
+                    f,
+                    "line {}, column {}: crate is not on a stack",
+                    line, column
+                )
+            }
+            ParseError::InvalidStep { line, found } => write!(
+                f,
+                "line {}: expected \"move <count> from <stack> to <stack>\", got \"{}\"",
+                line, found
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum StepError {
+    UnknownStack {
+        step: usize,
+        stack: usize,
+    },
+    NotEnoughCrates {
+        step: usize,
+        stack: usize,
+        requested: usize,
+        available: String,
+    },
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StepError::UnknownStack { step, stack } => {
+                write!(f, "step {}: there is no stack {}", step, stack)
+            }
+            StepError::NotEnoughCrates {
+                step,
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "step {}: can't move {} crates from stack {}, it only has {} [{}]",
+                step,
+                requested,
+                stack,
+                available.len(),
+                available
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Step {
     count: usize,
     from: usize,
     to: usize,
 }
 
-fn parse_input(input: &str) -> (Vec<Vec<char>>, Vec<Step>) {
-    let init_max_stack_height = input
-    .lines()
-    .position(|line| line.chars().any(|c| c.is_digit(10)))
-    .unwrap();
-    let stack_count = input
-    .lines()
-    .nth(init_max_stack_height)
-    .unwrap()
-    .to_string()
-    .split_whitespace()
-    .count();
-    let mut stacks = vec![Vec::new(); stack_count];
-    for line in input.lines().take(init_max_stack_height) {
-        for (index, crate_name) in line.chars().skip(1).step_by(4).enumerate() {
-            if crate_name != ' ' {
-                stacks[index].push(crate_name);
+impl Step {
+    fn parse(line: usize, text: &str) -> Result<Step, ParseError> {
+        let invalid_step = || ParseError::InvalidStep {
+            line,
+            found: text.to_string(),
+        };
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["move", count, "from", from, "to", to] => Ok(Step {
+                count: count.parse().map_err(|_| invalid_step())?,
+                from: from.parse().map_err(|_| invalid_step())?,
+                to: to.parse().map_err(|_| invalid_step())?,
+            }),
+            _ => Err(invalid_step()),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// Stacks of crates, bottom first. Stacks are numbered from 1 like in the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stacks(Vec<Vec<char>>);
+
+impl Stacks {
+    /// Parses a drawing like the one in the puzzle. The last line numbers the stacks, each
+    /// number ends in the column of its stack's crate letters.
+    fn parse(drawing: &str) -> Result<Stacks, ParseError> {
+        let lines: Vec<&str> = drawing.lines().collect();
+        let (labels, rows) = lines.split_last().ok_or(ParseError::MissingLabels)?;
+        let columns: Vec<usize> = labels
+            .char_indices()
+            .filter(|&(i, c)| {
+                c.is_ascii_digit() && !labels[i + 1..].starts_with(|c: char| c.is_ascii_digit())
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if columns.is_empty() {
+            return Err(ParseError::MissingLabels);
+        }
+
+        let mut stacks = vec![Vec::new(); columns.len()];
+        for (i, row) in rows.iter().enumerate().rev() {
+            let line = i + 1;
+            for (stack, &column) in stacks.iter_mut().zip(&columns) {
+                let cell: Vec<char> = row.chars().skip(column.saturating_sub(1)).take(3).collect();
+                // Columns are reported counting from 1.
+                let column = column + 1;
+                match cell.as_slice() {
+                    cell if cell.iter().all(|c| *c == ' ') => {}
+                    &['[', letter, ']'] if letter.is_ascii_alphabetic() => {
+                        if stack.len() != rows.len() - line {
+                            return Err(ParseError::FloatingCrate { line, column });
+                        }
+                        stack.push(letter);
+                    }
+                    _ => return Err(ParseError::InvalidCrate { line, column }),
+                }
             }
         }
+        Ok(Stacks(stacks))
     }
-    for stack in &mut stacks {
-        stack.reverse();
+
+    /// The crate on top of every stack, `None` if a stack is empty.
+    fn tops(&self) -> Option<String> {
+        self.0.iter().map(|stack| stack.last()).collect()
     }
-    let steps = input.lines().skip(init_max_stack_height + 2).map(|line| {
-        let mut words = line.split_whitespace().skip(1).step_by(2);
-        Step {
-            count: words.next().unwrap().parse().unwrap(),
-            from: words.next().unwrap().parse().unwrap(),
-            to: words.next().unwrap().parse().unwrap(),
+
+    fn stack(&mut self, step: usize, stack: usize) -> Result<&mut Vec<char>, StepError> {
+        stack
+            .checked_sub(1)
+            .and_then(|i| self.0.get_mut(i))
+            .ok_or(StepError::UnknownStack { step, stack })
+    }
+}
+
+/// Renders the stacks in the format of the puzzle, which `Stacks::parse` reads back.
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(letter) => format!("[{}]", letter),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
         }
-    })
-    .collect();
-    (stacks, steps)
+        let labels: Vec<String> = (1..=self.0.len()).map(|n| format!("{:>2} ", n)).collect();
+        write!(f, "{}", labels.join(" "))
+    }
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let (mut stacks, steps) = parse_input(input);
-    for step in steps {
-        for _ in 0..step.count {
-            let crate_name = stacks[step.from - 1].pop().unwrap();
-            stacks[step.to - 1].push(crate_name);
+/// A crane model, deciding in which order lifted crates end up on the target stack.
+trait Crane {
+    fn name(&self) -> &'static str;
+
+    /// Puts down crates lifted off a stack, `lifted` in the order they were stacked.
+    fn put_down(&self, lifted: Vec<char>, stack: &mut Vec<char>);
+
+    /// Moves crates for `step`, the `number`th step, after checking that it is possible.
+    fn execute(&self, stacks: &mut Stacks, number: usize, step: &Step) -> Result<(), StepError> {
+        // Check the target stack before lifting anything.
+        stacks.stack(number, step.to)?;
+        let from = stacks.stack(number, step.from)?;
+        if from.len() < step.count {
+            return Err(StepError::NotEnoughCrates {
+                step: number,
+                stack: step.from,
+                requested: step.count,
+                available: from.iter().collect(),
+            });
         }
+        let lifted = from.split_off(from.len() - step.count);
+        self.put_down(lifted, stacks.stack(number, step.to)?);
+        Ok(())
+    }
+
+    fn run(&self, stacks: &mut Stacks, steps: &[Step]) -> Result<(), StepError> {
+        steps
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, step)| self.execute(stacks, i + 1, step))
     }
-    stacks.iter().map(|stack| stack.last()).collect()
+}
+
+/// Moves one crate at a time, so lifted crates end up in reverse order.
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> &'static str {
+        "CrateMover 9000"
+    }
+
+    fn put_down(&self, lifted: Vec<char>, stack: &mut Vec<char>) {
+        stack.extend(lifted.into_iter().rev());
+    }
+}
+
+/// Moves all lifted crates at once, keeping their order.
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> &'static str {
+        "CrateMover 9001"
+    }
+
+    fn put_down(&self, lifted: Vec<char>, stack: &mut Vec<char>) {
+        stack.extend(lifted);
+    }
+}
+
+fn parse_input(input: &str) -> Result<(Stacks, Vec<Step>), ParseError> {
+    let (drawing, steps) = input.split_once("\n\n").ok_or(ParseError::MissingSteps)?;
+    let stacks = Stacks::parse(drawing)?;
+    let first_step_line = drawing.lines().count() + 2;
+    let steps = steps
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Step::parse(first_step_line + i, line))
+        .collect::<Result<_, _>>()?;
+    Ok((stacks, steps))
+}
+
+fn rearrange(input: &str, crane: &impl Crane) -> Option<String> {
+    let (mut stacks, steps) = parse_input(input).unwrap_or_else(|e| panic!("{}", e));
+    crane
+        .run(&mut stacks, &steps)
+        .unwrap_or_else(|e| panic!("{}", e));
+    stacks.tops()
+}
+
+pub fn part_one(input: &str) -> Option<String> {
+    rearrange(input, &CrateMover9000)
 }
 
 pub fn part_two(input: &str) -> Option<String> {
-    let (mut stacks, steps) = parse_input(input);
-    for step in steps {
-        let stack = &mut stacks[step.from - 1];
-        let stack_len = stack.len();
-        let crates = stack.split_off(stack_len - step.count);
-        stacks[step.to - 1].extend(crates);
+    rearrange(input, &CrateMover9001)
+}
+
+/// The stacks after every step.
+fn replay(crane: &impl Crane, mut stacks: Stacks, steps: &[Step]) -> String {
+    let mut lines = vec![crane.name().to_string(), stacks.to_string()];
+    for (i, step) in steps.iter().enumerate() {
+        lines.push(String::new());
+        match crane.execute(&mut stacks, i + 1, step) {
+            Ok(()) => lines.extend([step.to_string(), stacks.to_string()]),
+            Err(e) => {
+                lines.push(e.to_string());
+                break;
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn explain(input: &str) -> String {
+    match parse_input(input) {
+        Ok((stacks, steps)) => [
+            replay(&CrateMover9000, stacks.clone(), &steps),
+            replay(&CrateMover9001, stacks, &steps),
+        ]
+        .join("\n\n"),
+        Err(e) => e.to_string(),
     }
-    stacks.iter().map(|stack| stack.last()).collect()
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_05;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(5, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 5);
+    advent_of_code::fingerprint::check(5, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::explain::explain_from_args(|| explain(input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 5);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(5, part_one, part_two);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = advent_of_code::read_file("examples", 5);
+        let (stacks, _) = parse_input(&input).unwrap();
+        let drawing = input.split("\n\n").next().unwrap();
+        assert_eq!(stacks.to_string(), drawing);
+
+        let stacks = Stacks(vec![vec!['A'], vec![], vec!['B', 'C']]);
+        assert_eq!(Stacks::parse(&stacks.to_string()), Ok(stacks));
+        let stacks = Stacks((0..12).map(|i| vec!['A'; i % 3]).collect());
+        assert_eq!(Stacks::parse(&stacks.to_string()), Ok(stacks));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Stacks::parse("[A] [B\n 1   2 "),
+            Err(ParseError::InvalidCrate { line: 1, column: 6 })
+        );
+        assert_eq!(
+            Stacks::parse("[A]    \n    [B]\n 1   2 "),
+            Err(ParseError::FloatingCrate { line: 1, column: 2 })
+        );
+        assert_eq!(
+            parse_input("[A]\n 1 \n\nmove 1 from 1 to 2\nmove one from 1 to 2")
+                .unwrap_err()
+                .to_string(),
+            "line 5: expected \"move <count> from <stack> to <stack>\", got \"move one from 1 to 2\""
+        );
+    }
+
+    #[test]
+    fn test_cranes() {
+        let (stacks, _) = parse_input(&advent_of_code::read_file("examples", 5)).unwrap();
+        let step = Step::parse(1, "move 2 from 2 to 1").unwrap();
+        let mut moved = stacks.clone();
+        CrateMover9000.execute(&mut moved, 1, &step).unwrap();
+        assert_eq!(moved.0[0], vec!['Z', 'N', 'D', 'C']);
+        let mut moved = stacks.clone();
+        CrateMover9001.execute(&mut moved, 1, &step).unwrap();
+        assert_eq!(moved.0[0], vec!['Z', 'N', 'C', 'D']);
+
+        let step = Step::parse(1, "move 3 from 1 to 2").unwrap();
+        let mut moved = stacks.clone();
+        assert_eq!(
+            CrateMover9000
+                .run(&mut moved, &[step])
+                .unwrap_err()
+                .to_string(),
+            "step 1: can't move 3 crates from stack 1, it only has 2 [ZN]"
+        );
+        let step = Step::parse(1, "move 1 from 1 to 4").unwrap();
+        assert_eq!(
+            CrateMover9001.execute(&mut moved, 7, &step),
+            Err(StepError::UnknownStack { step: 7, stack: 4 })
+        );
+        assert_eq!(moved, stacks);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 5);
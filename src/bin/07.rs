@@ -1,113 +1,521 @@
-use std::str::Lines;
+use advent_of_code::params::Param;
+use std::{collections::BTreeMap, fmt, ops::ControlFlow};
 
-trait Sized {
-    fn size(&self) -> u32;
-}
+const DISK_SIZE: Param<u32> = Param::new("disk_size", 70_000_000);
+const SPACE_NEEDED: Param<u32> = Param::new("space_needed", 30_000_000);
+const SMALL_DIR_SIZE: u32 = 100_000;
+
+const ROOT: usize = 0;
 
+#[derive(Debug)]
 struct Dir {
-    files: Vec<File>,
-    dirs: Vec<Dir>,
+    name: String,
+    parent: Option<usize>,
+    dirs: BTreeMap<String, usize>,
+    files: BTreeMap<String, u32>,
+    /// Total size of the files in this directory and all its subdirectories.
+    size: u32,
 }
 
 impl Dir {
-    fn sum_small_sizes(&self) -> u32 {
-        let subdirs_size = self.dirs.iter().map(|d| d.sum_small_sizes()).sum::<u32>();
-        let size = self.size();
-        if size < 100000 {
-            size + subdirs_size
-        } else {
-            0 + subdirs_size
-        }
-    }
-
-    fn smallest_subdir(&self, size_greater_than: u32) -> u32 {
-        let mut smallest = u32::MAX;
-        if self.size() > size_greater_than {
-            smallest = self.size();
-        }
-        for d in self.dirs.iter() {
-            let size = d.size();
-            if size > size_greater_than {
-                let sub_smallest = d.smallest_subdir(size_greater_than);
-                if smallest == 0 || sub_smallest < smallest {
-                    smallest = sub_smallest;
-                }
-            }
+    fn new(name: &str, parent: Option<usize>) -> Self {
+        Dir {
+            name: name.to_string(),
+            parent,
+            dirs: BTreeMap::new(),
+            files: BTreeMap::new(),
+            size: 0,
         }
-        smallest
     }
 }
 
-impl Sized for Dir {
-    fn size(&self) -> u32 {
-        let files_size: u32 = self.files.iter().map(|f| f.size()).sum();
-        let dirs_size: u32 = self.dirs.iter().map(|d| d.size()).sum();
-        files_size + dirs_size
+#[derive(Debug, PartialEq, Eq)]
+enum TranscriptError {
+    AboveRoot { line: usize },
+    UnknownCommand { line: usize, found: String },
+    InvalidEntry { line: usize, found: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::AboveRoot { line } => {
+                write!(f, "line {}: `cd ..` in the root directory", line)
+            }
+            TranscriptError::UnknownCommand { line, found } => {
+                write!(f, "line {}: unknown command \"{}\"", line, found)
+            }
+            TranscriptError::InvalidEntry { line, found } => {
+                write!(f, "line {}: invalid `ls` output \"{}\"", line, found)
+            }
+        }
     }
 }
 
-struct File {
-    size: u32,
+/// Directory tree rebuilt from a terminal transcript. Directories are stored in the order they
+/// were discovered, so every directory comes after its parent and `ROOT` is the first one.
+#[derive(Debug)]
+struct FileSystem {
+    dirs: Vec<Dir>,
 }
 
-impl Sized for File {
+impl FileSystem {
+    fn from_transcript(input: &str) -> Result<Self, TranscriptError> {
+        let mut fs = FileSystem {
+            dirs: vec![Dir::new("/", None)],
+        };
+        let mut cwd = ROOT;
+        for (i, line) in input.lines().enumerate() {
+            let line_number = i + 1;
+            if let Some(command) = line.strip_prefix("$ ") {
+                match command.split_once(' ') {
+                    Some(("cd", "/")) => cwd = ROOT,
+                    Some(("cd", "..")) => {
+                        cwd = fs.dirs[cwd]
+                            .parent
+                            .ok_or(TranscriptError::AboveRoot { line: line_number })?
+                    }
+                    Some(("cd", name)) => cwd = fs.subdir(cwd, name),
+                    None if command == "ls" => {}
+                    _ => {
+                        return Err(TranscriptError::UnknownCommand {
+                            line: line_number,
+                            found: command.to_string(),
+                        })
+                    }
+                }
+            } else {
+                let invalid_entry = || TranscriptError::InvalidEntry {
+                    line: line_number,
+                    found: line.to_string(),
+                };
+                match line.split_once(' ').ok_or_else(invalid_entry)? {
+                    ("dir", name) => {
+                        fs.subdir(cwd, name);
+                    }
+                    (size, name) => {
+                        let size = size.parse().map_err(|_| invalid_entry())?;
+                        fs.dirs[cwd].files.insert(name.to_string(), size);
+                    }
+                }
+            }
+        }
+        fs.compute_sizes();
+        Ok(fs)
+    }
+
+    /// The subdirectory `name` of `parent`, created if it wasn't listed yet.
+    fn subdir(&mut self, parent: usize, name: &str) -> usize {
+        if let Some(&id) = self.dirs[parent].dirs.get(name) {
+            return id;
+        }
+        let id = self.dirs.len();
+        self.dirs.push(Dir::new(name, Some(parent)));
+        self.dirs[parent].dirs.insert(name.to_string(), id);
+        id
+    }
+
+    /// Children come after their parents, so walking backwards adds each directory's total to
+    /// its parent after the total is complete.
+    fn compute_sizes(&mut self) {
+        for id in (0..self.dirs.len()).rev() {
+            let dir = &mut self.dirs[id];
+            dir.size += dir.files.values().sum::<u32>();
+            let (size, parent) = (dir.size, dir.parent);
+            if let Some(parent) = parent {
+                self.dirs[parent].size += size;
+            }
+        }
+    }
+
     fn size(&self) -> u32 {
-        self.size
-    }
-}
-
-fn parse_input(lines: &mut std::str::Lines<'_>) -> Dir {
-    let mut root = Dir {
-        files: Vec::new(),
-        dirs: Vec::new(),
-    };
-    while let Some(next_line) = lines.next() {
-        if next_line == "$ cd .." {
-            return root;
-        } else if next_line == "$ ls" {
-            continue;
-        } else if next_line.starts_with("$ cd ") {
-            let dir = parse_input(lines);
-            root.dirs.push(dir);
-        } else if next_line.starts_with("dir") {
-            continue;
-        } else {
-            let mut parts = next_line.split_whitespace();
-            let size = parts.next().unwrap().parse::<u32>().unwrap();
-            root.files.push(File {
-                size: size,
-            });
+        self.dirs[ROOT].size
+    }
+
+    fn path(&self, id: usize) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.dirs[current].parent {
+            names.push(self.dirs[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Ids of all directories, depth first with subdirectories sorted by name.
+    fn walk(&self) -> Vec<usize> {
+        let mut ids = Vec::new();
+        let mut stack = vec![ROOT];
+        while let Some(id) = stack.pop() {
+            ids.push(id);
+            stack.extend(self.dirs[id].dirs.values().rev());
+        }
+        ids
+    }
+
+    /// Listing in the style of the puzzle description.
+    fn tree(&self) -> String {
+        let mut lines = Vec::new();
+        self.tree_lines(ROOT, 0, &mut lines);
+        lines.join("\n")
+    }
+
+    fn tree_lines(&self, id: usize, depth: usize, lines: &mut Vec<String>) {
+        let dir = &self.dirs[id];
+        let indent = "  ".repeat(depth);
+        lines.push(format!("{}- {} (dir, size={})", indent, dir.name, dir.size));
+        for &subdir in dir.dirs.values() {
+            self.tree_lines(subdir, depth + 1, lines);
+        }
+        for (name, size) in &dir.files {
+            lines.push(format!("{}  - {} (file, size={})", indent, name, size));
         }
     }
-    root
+
+    /// Total size of every directory in the style of `du`, one `size<TAB>path` line each.
+    fn du(&self) -> String {
+        self.walk()
+            .into_iter()
+            .map(|id| format!("{}\t{}", self.dirs[id].size, self.path(id)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn load(input: &str) -> FileSystem {
+    FileSystem::from_transcript(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut lines: Lines = input.lines();
-    lines.next();
-    let root = parse_input(&mut lines);
-    Some(root.sum_small_sizes())
+    let fs = load(input);
+    let sum = fs
+        .dirs
+        .iter()
+        .map(|dir| dir.size)
+        .filter(|&size| size < SMALL_DIR_SIZE)
+        .sum();
+    Some(sum)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut lines: Lines = input.lines();
-    lines.next();
-    let root = parse_input(&mut lines);
-    let size_root = root.size();
-    let space_to_free = size_root - 40000000;
-    Some(root.smallest_subdir(space_to_free))
+    let fs = load(input);
+    let planner = Planner::new(&fs, DISK_SIZE.get(), SPACE_NEEDED.get());
+    planner.best_single().map(|plan| plan.total)
+}
+
+/// Directories to delete, none of them inside another one.
+#[derive(Debug, PartialEq, Eq)]
+struct Plan {
+    total: u32,
+    dirs: Vec<usize>,
+}
+
+/// Set of totals below a limit.
+#[derive(Debug, Clone)]
+struct Totals {
+    limit: u32,
+    words: Vec<u64>,
+}
+
+impl Totals {
+    fn new(limit: u32) -> Self {
+        Totals {
+            limit,
+            words: vec![0; (limit as usize).div_ceil(64)],
+        }
+    }
+
+    fn contains(&self, total: u32) -> bool {
+        total < self.limit && self.words[total as usize / 64] & (1 << (total % 64)) != 0
+    }
+
+    fn insert(&mut self, total: u32) {
+        if total < self.limit {
+            self.words[total as usize / 64] |= 1 << (total % 64);
+        }
+    }
+
+    /// Inserts every total of `other` increased by `shift`, dropping those reaching the limit.
+    fn insert_shifted(&mut self, other: &Totals, shift: u32) {
+        let (word_shift, bit_shift) = (shift as usize / 64, shift % 64);
+        let len = self.words.len();
+        for i in (0..len.saturating_sub(word_shift)).rev() {
+            self.words[i + word_shift] |= other.words[i] << bit_shift;
+            if bit_shift > 0 && i + word_shift + 1 < len {
+                self.words[i + word_shift + 1] |= other.words[i] >> (64 - bit_shift);
+            }
+        }
+        if !self.limit.is_multiple_of(64) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (self.limit % 64)) - 1;
+            }
+        }
+    }
+
+    /// The smallest total that is at least `min`.
+    fn first_at_least(&self, min: u32) -> Option<u32> {
+        let start = min as usize / 64;
+        let first = self.words.get(start)? & (u64::MAX << (min % 64));
+        std::iter::once(first)
+            .chain(self.words[start + 1..].iter().copied())
+            .enumerate()
+            .find(|(_, word)| *word != 0)
+            .map(|(i, word)| ((start + i) * 64) as u32 + word.trailing_zeros())
+    }
+}
+
+/// Finds directories to delete to get `space_needed` free on a disk of `disk_size`.
+struct Planner<'a> {
+    fs: &'a FileSystem,
+    disk_size: u32,
+    space_to_free: u32,
+}
+
+impl<'a> Planner<'a> {
+    fn new(fs: &'a FileSystem, disk_size: u32, space_needed: u32) -> Self {
+        let free = disk_size.saturating_sub(fs.size());
+        Planner {
+            fs,
+            disk_size,
+            space_to_free: space_needed.saturating_sub(free),
+        }
+    }
+
+    /// The smallest directory that frees enough space on its own.
+    fn best_single(&self) -> Option<Plan> {
+        (0..self.fs.dirs.len())
+            .filter(|&id| self.fs.dirs[id].size >= self.space_to_free)
+            .min_by_key(|&id| self.fs.dirs[id].size)
+            .map(|id| Plan {
+                total: self.fs.dirs[id].size,
+                dirs: vec![id],
+            })
+    }
+
+    /// The set of directories with the smallest total that frees enough space.
+    fn best_set(&self) -> Option<Plan> {
+        if self.space_to_free == 0 {
+            return Some(Plan {
+                total: 0,
+                dirs: Vec::new(),
+            });
+        }
+        let mut best: Option<(u32, usize, u32)> = None;
+        self.walk(|id, before, _| {
+            let size = self.fs.dirs[id].size;
+            if let Some(rest) = before.first_at_least(self.space_to_free.saturating_sub(size)) {
+                if best.is_none_or(|(total, _, _)| rest + size < total) {
+                    best = Some((rest + size, id, rest));
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+        let (total, id, mut rest) = best?;
+
+        let mut dirs = vec![id];
+        while rest > 0 {
+            let id = self.first_reaching(rest);
+            dirs.push(id);
+            rest -= self.fs.dirs[id].size;
+        }
+        dirs.sort_by_key(|&id| self.fs.path(id));
+        Some(Plan { total, dirs })
+    }
+
+    /// The directory whose deletion first made `total` reachable during the walk. The totals
+    /// reachable before it only contain directories the walk left earlier, so following these
+    /// directories back never picks one inside another.
+    fn first_reaching(&self, total: u32) -> usize {
+        let found = self.walk(|id, before, reachable| {
+            let size = self.fs.dirs[id].size;
+            if !reachable.contains(total) && total >= size && before.contains(total - size) {
+                ControlFlow::Break(id)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        found.unwrap_or_else(|| panic!("{} is not reachable", total))
+    }
+
+    /// Walks the directories depth first and calls `visit` when leaving each one, with the totals
+    /// that were reachable when entering it and the totals reachable now, until `visit` breaks.
+    ///
+    /// Totals only grow during the walk: looking inside a directory instead of deleting it is
+    /// always possible, so one set is shared by all directories. When the walk leaves a directory,
+    /// deleting it adds its size to the totals that were reachable when entering it, as none of
+    /// them contains a directory inside it.
+    fn walk<B>(
+        &self,
+        mut visit: impl FnMut(usize, &Totals, &Totals) -> ControlFlow<B>,
+    ) -> Option<B> {
+        let order = self.fs.walk();
+        let mut subtree_len = vec![1; self.fs.dirs.len()];
+        for id in (0..self.fs.dirs.len()).rev() {
+            if let Some(parent) = self.fs.dirs[id].parent {
+                subtree_len[parent] += subtree_len[id];
+            }
+        }
+
+        let mut reachable = Totals::new(self.space_to_free);
+        reachable.insert(0);
+        // Directories the walk is inside of, with the position where it leaves them.
+        let mut open: Vec<(usize, usize, Totals)> = Vec::new();
+        for position in 0..=order.len() {
+            while open.last().is_some_and(|(end, _, _)| *end == position) {
+                let (_, id, before) = open.pop().unwrap();
+                if let ControlFlow::Break(found) = visit(id, &before, &reachable) {
+                    return Some(found);
+                }
+                reachable.insert_shifted(&before, self.fs.dirs[id].size);
+            }
+            if let Some(&id) = order.get(position) {
+                open.push((position + subtree_len[id], id, reachable.clone()));
+            }
+        }
+        None
+    }
+
+    fn describe(&self, plan: &Plan) -> String {
+        plan.dirs
+            .iter()
+            .map(|&id| format!("  {:>10}  {}", self.fs.dirs[id].size, self.fs.path(id)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn report(&self) -> String {
+        let used = self.fs.size();
+        let mut lines = vec![format!(
+            "{} of {} used, {} free, {} to free",
+            used,
+            self.disk_size,
+            self.disk_size.saturating_sub(used),
+            self.space_to_free
+        )];
+        if self.space_to_free == 0 {
+            lines.push("nothing to delete".to_string());
+            return lines.join("\n");
+        }
+        match self.best_single() {
+            Some(plan) => lines.push(format!(
+                "smallest single directory, {}:\n{}",
+                plan.total,
+                self.describe(&plan)
+            )),
+            None => lines.push("no directory frees enough space".to_string()),
+        }
+        if let Some(plan) = self.best_set() {
+            lines.push(format!(
+                "smallest set of directories, {}:\n{}",
+                plan.total,
+                self.describe(&plan)
+            ));
+        }
+        lines.join("\n")
+    }
 }
 
+fn explain(input: &str) -> String {
+    let fs = load(input);
+    Planner::new(&fs, DISK_SIZE.get(), SPACE_NEEDED.get()).report()
+}
+
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_07;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(7, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 7);
+    advent_of_code::fingerprint::check(7, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::explain::explain_from_args(|| explain(input));
+
+    let mut args = pico_args::Arguments::from_env();
+    let (tree, du) = (args.contains("--tree"), args.contains("--du"));
+    if tree || du {
+        match FileSystem::from_transcript(input) {
+            Ok(fs) => {
+                if tree {
+                    println!("{}", fs.tree());
+                }
+                if du {
+                    println!("{}", fs.du());
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 7);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(7, part_one, part_two);
+    }
+
+    #[test]
+    fn test_from_transcript() {
+        let input = "$ cd /\n$ ls\ndir a\n10 b.txt\n$ cd a\n$ ls\n5 c\n$ cd /\n$ ls\ndir a\n10 b.txt\n$ cd a\n$ cd ..\n";
+        let fs = FileSystem::from_transcript(input).unwrap();
+        assert_eq!(fs.dirs.len(), 2);
+        assert_eq!(fs.size(), 15);
+        assert_eq!(fs.du(), "15\t/\n5\t/a");
+
+        assert_eq!(
+            FileSystem::from_transcript("$ cd /\n$ cd ..\n").unwrap_err(),
+            TranscriptError::AboveRoot { line: 2 }
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ ls\nabc d\n")
+                .unwrap_err()
+                .to_string(),
+            "line 2: invalid `ls` output \"abc d\""
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        let input = advent_of_code::read_file("examples", 7);
+        let fs = load(&input);
+        let tree = fs.tree();
+        assert_eq!(tree.lines().next(), Some("- / (dir, size=48381165)"));
+        assert!(tree.contains(
+            "\n  - a (dir, size=94853)\n    - e (dir, size=584)\n      - i (file, size=584)\n"
+        ));
+        assert!(fs.du().contains("584\t/a/e\n"));
+    }
+
+    #[test]
+    fn test_planner() {
+        let input = "$ cd /\n$ ls\ndir a\ndir b\ndir c\n$ cd a\n$ ls\n60 x\n$ cd ..\n$ cd b\n$ ls\n50 y\n$ cd /\n$ cd c\n$ ls\ndir d\n5 z\n$ cd d\n$ ls\n40 w\n";
+        let fs = load(input);
+        let planner = Planner::new(&fs, 200, 135);
+        assert_eq!(planner.space_to_free, 90);
+        assert_eq!(
+            planner.best_single(),
+            Some(Plan {
+                total: 155,
+                dirs: vec![ROOT]
+            })
+        );
+        let plan = planner.best_set().unwrap();
+        assert_eq!(plan.total, 90);
+        let paths: Vec<String> = plan.dirs.iter().map(|&id| fs.path(id)).collect();
+        assert_eq!(paths, vec!["/b", "/c/d"]);
+
+        assert_eq!(Planner::new(&fs, 200, 300).best_set(), None);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 7);
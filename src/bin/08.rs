@@ -1,3 +1,5 @@
+use advent_of_code::helpers::grid::{Grid, Pos, NEIGHBOURS_4};
+
 #[derive(Clone, Copy, Debug)]
 struct Tree {
     height: u32,
@@ -6,9 +8,7 @@ struct Tree {
 
 #[derive(Debug)]
 struct Forest {
-    trees: Vec<Vec<Tree>>,
-    width: usize,
-    height: usize,
+    trees: Grid<Tree>,
 }
 
 // Return number of trees with height but stop first time we see a tree with height = current_height
@@ -27,107 +27,60 @@ fn visible_trees<'a>(trees: impl IntoIterator<Item = &'a Tree>, current_height:
 
 impl Forest {
     fn count_visible(&self) -> u32 {
-        self.trees
-            .iter()
-            .map(|row| row.iter().filter(|&t| t.visible).count() as u32)
-            .sum()
+        self.trees.cells().iter().filter(|t| t.visible).count() as u32
+    }
+
+    fn is_visible(&self, pos: Pos) -> bool {
+        let height = self.trees[pos].height;
+        NEIGHBOURS_4.iter().any(|direction| {
+            self.trees
+                .ray(pos, *direction)
+                .all(|(_, tree)| tree.height < height)
+        })
     }
 
     fn update_visibility(&mut self) {
-        for i in 0..self.height {
-            let mut current_max_height = 0;
-            for j in 0..self.width {
-                let mut current_tree = &mut self.trees[i][j];
-                if j == 0 {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                } else if current_tree.height > current_max_height {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                }
-            }
-            current_max_height = 0;
-            for j in (0..self.width).rev() {
-                let mut current_tree = &mut self.trees[i][j];
-                if j == self.width - 1 {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                } else if current_tree.height > current_max_height {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                }
-            }
-        }
-        for j in 0..self.width {
-            let mut current_max_height = 0;
-            for i in 0..self.height {
-                let mut current_tree = &mut self.trees[i][j];
-                if i == 0 {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                } else if current_tree.height > current_max_height {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                }
-            }
-            current_max_height = 0;
-            for i in (0..self.height).rev() {
-                let mut current_tree = &mut self.trees[i][j];
-                if i == self.height - 1 {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                } else if current_tree.height > current_max_height {
-                    current_tree.visible = true;
-                    current_max_height = current_tree.height;
-                }
-            }
+        let visible: Vec<bool> = self
+            .trees
+            .positions()
+            .map(|pos| self.is_visible(pos))
+            .collect();
+        for (tree, visible) in self.trees.iter_mut().zip(visible) {
+            tree.visible = visible;
         }
     }
 
-
-    fn scenic_score(&self, i: usize, j: usize) -> u32 {
-        let current_height = self.trees[i][j].height;
-        let score_left = visible_trees(self.trees[i][0..j].iter().rev(), current_height);
-        let score_right = visible_trees(self.trees[i][j + 1..].iter(), current_height);
-        let score_up = visible_trees(self.trees[0..i].into_iter().map(|row| &row[j]).rev(), current_height);
-        let score_down = visible_trees(self.trees[i + 1..].into_iter().map(|row| &row[j]), current_height);
-        (score_left * score_right * score_up * score_down) as u32
+    fn scenic_score(&self, pos: Pos) -> u32 {
+        let current_height = self.trees[pos].height;
+        NEIGHBOURS_4
+            .iter()
+            .map(|direction| {
+                visible_trees(
+                    self.trees.ray(pos, *direction).map(|(_, tree)| tree),
+                    current_height,
+                )
+            })
+            .product()
     }
 
     fn best_scenic_score(&self) -> u32 {
-        let mut best_score = 0;
-        for i in 0..self.height {
-            for j in 0..self.width {
-                let score = self.scenic_score(i, j);
-                if score > best_score {
-                    best_score = score;
-                }
-            }
-        }
-        best_score
+        self.trees
+            .positions()
+            .map(|pos| self.scenic_score(pos))
+            .max()
+            .unwrap_or(0)
     }
 }
 
 fn parse_input(input: &str) -> Forest {
-    let mut trees = Vec::new();
-    for line in input.lines() {
-        let mut row = Vec::new();
-        for c in line.chars() {
-            let height = c.to_digit(10).unwrap();
-            row.push(Tree {
-                height,
-                visible: false,
-            });
-        }
-        trees.push(row);
-    }
-    let width = trees[0].len();
-    let height = trees.len();
-    Forest {
-        trees,
-        height,
-        width,
-    }
+    let trees = Grid::parse(input, |c| {
+        c.to_digit(10).map(|height| Tree {
+            height,
+            visible: false,
+        })
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+    Forest { trees }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -141,8 +94,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(forest.best_scenic_score())
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_08;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(8, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 8);
+    advent_of_code::fingerprint::check(8, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -151,6 +108,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 8);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(8, part_one, part_two);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 8);
@@ -1,123 +1,142 @@
+use advent_of_code::{
+    helpers::point::{Direction, Point2},
+    recorder::{Frame, Recorder},
+};
 use std::collections::HashSet;
 
-#[derive(Clone)]
-struct Knot {
-    x: i32,
-    y: i32,
-}
-
-impl Knot {
-    fn new() -> Self {
-        Knot { x: 0, y: 0 }
-    }
+type Knot = Point2<i32>;
 
-    fn move_unit(&mut self, direction: char) {
-        match direction {
-            'U' => self.y += 1,
-            'D' => self.y -= 1,
-            'L' => self.x -= 1,
-            'R' => self.x += 1,
-            _ => panic!("Invalid direction"),
-        }
-    }
-
-    fn move_after_step(&mut self, other: Knot) {
-        if self.x < other.x - 1 {
-            self.x = other.x - 1;
-            if self.y < other.y {
-                self.y += 1;
-            } else if self.y > other.y {
-                self.y -= 1;
-            }
-        } else if self.x > other.x + 1 {
-            self.x = other.x + 1;
-            if self.y < other.y {
-                self.y += 1;
-            } else if self.y > other.y {
-                self.y -= 1;
-            }
-        } else if self.y < other.y - 1 {
-            self.y = other.y - 1;
-            if self.x < other.x {
-                self.x += 1;
-            } else if self.x > other.x {
-                self.x -= 1;
-            }
-        } else if self.y > other.y + 1 {
-            self.y = other.y + 1;
-            if self.x < other.x {
-                self.x += 1;
-            } else if self.x > other.x {
-                self.x -= 1;
-            }
-        }
+/// Moves `knot` one step towards `other` if they are no longer touching.
+fn follow(knot: &mut Knot, other: Knot) {
+    if knot.chebyshev(&other) > 1 {
+        *knot += (other - *knot).signum();
     }
 }
 
 struct Rope {
     knots: Vec<Knot>,
-    visited: HashSet<(i32, i32)>,
+    visited: HashSet<Knot>,
 }
 
 impl Rope {
     fn new(knot_count: usize) -> Self {
         Rope {
-            knots: vec![Knot::new(); knot_count],
+            knots: vec![Knot::default(); knot_count],
             visited: HashSet::new(),
         }
     }
 
-    fn move_unit(&mut self, direction: char) {
-        self.knots[0].move_unit(direction);
+    fn move_unit(&mut self, direction: Direction, recorder: &mut Recorder) {
+        self.knots[0] += direction.offset();
         for i in 1..self.knots.len() {
-            let other = self.knots[i - 1].clone();
-            self.knots[i].move_after_step(other);
+            let other = self.knots[i - 1];
+            follow(&mut self.knots[i], other);
         }
-        self.visited.insert((
-            self.knots[self.knots.len() - 1].x,
-            self.knots[self.knots.len() - 1].y,
-        ));
+        self.visited.insert(self.knots[self.knots.len() - 1]);
+        recorder.record(|| self.to_frame());
     }
 
-    fn move_steps(&mut self, direction: char, steps: u32) {
+    fn move_steps(&mut self, direction: Direction, steps: u32, recorder: &mut Recorder) {
         for _ in 0..steps {
-            self.move_unit(direction);
+            self.move_unit(direction, recorder);
+        }
+    }
+
+    fn knot_char(&self, index: usize) -> char {
+        if index == 0 {
+            'H'
+        } else if self.knots.len() == 2 {
+            'T'
+        } else {
+            char::from_digit(index as u32, 36).unwrap()
+        }
+    }
+
+    // Draws the rope in the puzzle's format: knots on top of visited positions (`#`) and start (`s`).
+    fn to_frame(&self) -> Frame {
+        let points = self
+            .visited
+            .iter()
+            .copied()
+            .chain(self.knots.iter().copied())
+            .chain([Knot::default()]);
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (0, 0, 0, 0);
+        for Knot { x, y } in points {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
         }
+
+        let rows = (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        let point = Knot::new(x, y);
+                        if let Some(index) = self.knots.iter().position(|k| *k == point) {
+                            self.knot_char(index)
+                        } else if point == Knot::default() {
+                            's'
+                        } else if self.visited.contains(&point) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Frame::new(rows)
     }
 }
 
-fn process_input(input: &str, knot_count: usize) -> Option<u32> {
+fn process_input(input: &str, knot_count: usize, recorder: &mut Recorder) -> Option<u32> {
     let mut rope = Rope::new(knot_count);
     for line in input.lines() {
         let mut parts = line.split_whitespace();
-        let dir = parts.next().unwrap().chars().next().unwrap();
+        let dir: Direction = parts.next().unwrap().parse().unwrap();
         let steps = parts.next().unwrap().parse::<u32>().unwrap();
-        rope.move_steps(dir, steps);
-        // rope.print(true, false);
-        // println!();
+        rope.move_steps(dir, steps, recorder);
     }
-    // rope.print(false, true);
     Some(rope.visited.len() as u32)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    process_input(input, 2)
+    process_input(input, 2, &mut Recorder::disabled())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    process_input(input, 10)
+    process_input(input, 10, &mut Recorder::disabled())
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_09;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(9, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 9);
+    advent_of_code::fingerprint::check(9, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::recorder::record_from_args(|recorder| {
+        process_input(input, 10, recorder);
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 9);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(9, part_one, part_two);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 9);
@@ -1,116 +1,446 @@
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut value = 1;
-    let mut cycle = 1;
-    let mut signal_strength_sum = 0;
-
-    let mut check_cycle = |cycle, value| {
-        if cycle % 40 == 20 {
-            let signal_strength = cycle * value;
-            println!(
-                "Cycle: {}, Value: {}, Signal Strength: {}",
-                cycle, value, signal_strength
-            );
-            signal_strength_sum += signal_strength;
-        };
-    };
+use std::{
+    collections::BTreeSet,
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+const SCREEN_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+impl Instruction {
+    fn cycles(&self) -> u32 {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(value) => write!(f, "addx {}", value),
+        }
+    }
+}
 
-    for line in input.lines() {
-        let mut parts = line.split_whitespace();
-        let instruction = parts.next().unwrap();
-        match instruction {
-            "noop" => {
-                cycle += 1;
+#[derive(Debug, PartialEq, Eq)]
+enum ProgramError {
+    UnknownInstruction { line: usize, found: String },
+    InvalidArgument { line: usize, found: String },
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgramError::UnknownInstruction { line, found } => {
+                write!(f, "line {}: unknown instruction \"{}\"", line, found)
             }
-            "addx" => {
-                let input = parts.next().unwrap().parse::<i32>().unwrap();
-                cycle += 1;
-                check_cycle(cycle, value);
-                cycle += 1;
-                value += input;
+            ProgramError::InvalidArgument { line, found } => {
+                write!(f, "line {}: invalid argument \"{}\"", line, found)
             }
-            _ => {
-                panic!("Invalid instruction!")
+        }
+    }
+}
+
+fn parse_instruction(line: usize, text: &str) -> Result<Instruction, ProgramError> {
+    let invalid_argument = || ProgramError::InvalidArgument {
+        line,
+        found: text.to_string(),
+    };
+    let mut parts = text.split_whitespace();
+    let instruction = match parts.next() {
+        Some("noop") => Instruction::Noop,
+        Some("addx") => {
+            let value = parts.next().ok_or_else(invalid_argument)?;
+            Instruction::Addx(value.parse().map_err(|_| invalid_argument())?)
+        }
+        _ => {
+            return Err(ProgramError::UnknownInstruction {
+                line,
+                found: text.to_string(),
+            })
+        }
+    };
+    match parts.next() {
+        Some(_) => Err(invalid_argument()),
+        None => Ok(instruction),
+    }
+}
+
+fn parse_program(input: &str) -> Result<Vec<Instruction>, ProgramError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| parse_instruction(i + 1, text))
+        .collect()
+}
+
+/// The CPU during a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct State {
+    /// Cycles are counted from 1.
+    cycle: u32,
+    x: i32,
+    /// Line of the executing instruction, counted from 1.
+    line: usize,
+    instruction: Instruction,
+    /// Whether the instruction started in this cycle.
+    starts_instruction: bool,
+}
+
+/// Executes a program cycle by cycle. As an iterator it yields the state during every cycle.
+struct Cpu<'a> {
+    program: &'a [Instruction],
+    pc: usize,
+    x: i32,
+    cycle: u32,
+    /// Cycles left until the current instruction completes, 0 if it didn't start yet.
+    busy: u32,
+}
+
+impl<'a> Cpu<'a> {
+    fn new(program: &'a [Instruction]) -> Self {
+        Cpu {
+            program,
+            pc: 0,
+            x: 1,
+            cycle: 1,
+            busy: 0,
+        }
+    }
+
+    fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// Runs one cycle and returns the state during it, `None` once the program has ended.
+    fn step(&mut self) -> Option<State> {
+        let instruction = *self.program.get(self.pc)?;
+        let starts_instruction = self.busy == 0;
+        if starts_instruction {
+            self.busy = instruction.cycles();
+        }
+        let state = State {
+            cycle: self.cycle,
+            x: self.x,
+            line: self.pc + 1,
+            instruction,
+            starts_instruction,
+        };
+
+        self.cycle += 1;
+        self.busy -= 1;
+        if self.busy == 0 {
+            if let Instruction::Addx(value) = instruction {
+                self.x += value;
             }
+            self.pc += 1;
         }
-        check_cycle(cycle, value);
+        Some(state)
+    }
+}
+
+impl Iterator for Cpu<'_> {
+    type Item = State;
+
+    fn next(&mut self) -> Option<State> {
+        self.step()
     }
+}
+
+fn signal_strength(state: &State) -> i32 {
+    state.cycle as i32 * state.x
+}
+
+/// Whether the sprite at `x` covers the pixel drawn during `cycle`.
+fn is_lit(cycle: u32, x: i32) -> bool {
+    let column = (cycle as usize - 1) % SCREEN_WIDTH;
+    (column as i32 - x).abs() <= 1
+}
+
+fn render(states: impl Iterator<Item = State>) -> String {
+    let pixels: Vec<char> = states
+        .map(|state| {
+            if is_lit(state.cycle, state.x) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    pixels
+        .chunks(SCREEN_WIDTH)
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn load(input: &str) -> Vec<Instruction> {
+    parse_program(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let program = load(input);
+    let signal_strength_sum: i32 = Cpu::new(&program)
+        .filter(|state| state.cycle as usize % SCREEN_WIDTH == 20)
+        .inspect(|state| {
+            advent_of_code::debug!(
+                "Cycle: {}, Value: {}, Signal Strength: {}",
+                state.cycle,
+                state.x,
+                signal_strength(state)
+            )
+        })
+        .map(|state| signal_strength(&state))
+        .sum();
     Some(signal_strength_sum as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut sprite_position: i32 = 1;
-    let mut cycle: i32 = 1;
-    let mut screen = Vec::new();
+pub fn part_two(input: &str) -> Option<String> {
+    let program = load(input);
+    Some(render(Cpu::new(&program)))
+}
 
-    let mut draw = |cycle, sprite_position| {
-        let cursor: usize = ((cycle - 1) % 40) as usize;
-        // let mut sprite_viz = vec!['.'; 40];
-        // if (0..39).contains(&sprite_position) {
-        //     sprite_viz[sprite_position as usize] = 'X';
-        //     sprite_viz[(sprite_position - 1) as usize] = 'X';
-        //     sprite_viz[(sprite_position + 1) as usize] = 'X';
-        // }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Breakpoint {
+    Cycle(u32),
+    Line(usize),
+}
 
-        // let mut cursor_viz = vec!['.'; 40];
-        // cursor_viz[cursor] = 'O';
+impl Breakpoint {
+    fn hit(&self, state: &State) -> bool {
+        match self {
+            Breakpoint::Cycle(cycle) => state.cycle == *cycle,
+            Breakpoint::Line(line) => state.line == *line && state.starts_instruction,
+        }
+    }
+}
 
-        let sprite_distance: i32 = (cursor as i32 % 40) - sprite_position;
-        if sprite_distance.abs() <= 1 {
-            screen.push('#');
-        } else {
-            screen.push('.');
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Cycle(cycle) => write!(f, "cycle {}", cycle),
+            Breakpoint::Line(line) => write!(f, "line {}", line),
         }
+    }
+}
 
-        // println!("Cycle: {}", cycle);
-        // println!("{:?}", sprite_viz.iter().cloned().collect::<String>());
-        // println!("{:?}", cursor_viz.iter().cloned().collect::<String>());
-        // screen
-        //     .chunks(40)
-        //     .for_each(|line| println!("{:?}", line.iter().cloned().collect::<String>()));
-    };
+const DEBUGGER_HELP: &str = "commands:
+  step [n]          run one or n cycles
+  continue          run until a breakpoint or the end of the program
+  break <cycle>     stop during a cycle
+  break line <n>    stop when the instruction on line n starts
+  delete            remove all breakpoints
+  print             show the last state and the screen so far
+  quit";
 
-    for line in input.lines() {
-        draw(cycle, sprite_position);
+/// Terminal debugger for a program: single-steps cycles and stops at breakpoints.
+struct Debugger<'a> {
+    cpu: Cpu<'a>,
+    breakpoints: BTreeSet<Breakpoint>,
+    states: Vec<State>,
+}
+
+impl<'a> Debugger<'a> {
+    fn new(program: &'a [Instruction]) -> Self {
+        Debugger {
+            cpu: Cpu::new(program),
+            breakpoints: BTreeSet::new(),
+            states: Vec::new(),
+        }
+    }
 
-        let mut parts = line.split_whitespace();
-        let instruction = parts.next().unwrap();
-        match instruction {
-            "noop" => {
-                cycle += 1;
+    fn describe(state: &State) -> String {
+        format!(
+            "cycle {:>3}  X={:<3} line {:>3}: {}{}",
+            state.cycle,
+            state.x,
+            state.line,
+            state.instruction,
+            if state.starts_instruction {
+                ""
+            } else {
+                " (cont.)"
             }
-            "addx" => {
-                let input = parts.next().unwrap().parse::<i32>().unwrap();
-                cycle += 1;
-                draw(cycle, sprite_position);
-                cycle += 1;
-                sprite_position += input;
+        )
+    }
+
+    fn step(&mut self) -> Option<State> {
+        let state = self.cpu.step()?;
+        self.states.push(state);
+        Some(state)
+    }
+
+    /// Runs `command` and returns what it prints, `None` for `quit`.
+    fn execute(&mut self, command: &str) -> Option<String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let output = match words.as_slice() {
+            [] | ["s" | "step"] => self.run_steps(1),
+            ["s" | "step", n] => match n.parse() {
+                Ok(n) => self.run_steps(n),
+                Err(_) => format!("not a number of cycles: {}", n),
+            },
+            ["c" | "continue"] => self.run_to_breakpoint(),
+            ["b" | "break", "line", n] => self.add_breakpoint(n.parse().ok().map(Breakpoint::Line)),
+            ["b" | "break", n] => self.add_breakpoint(n.parse().ok().map(Breakpoint::Cycle)),
+            ["d" | "delete"] => {
+                self.breakpoints.clear();
+                "deleted all breakpoints".to_string()
             }
-            _ => {
-                panic!("Invalid instruction!")
+            ["p" | "print"] => match self.states.last() {
+                Some(state) => format!(
+                    "{}\n{}",
+                    Debugger::describe(state),
+                    render(self.states.iter().copied())
+                ),
+                None => "program did not start yet".to_string(),
+            },
+            ["q" | "quit"] => return None,
+            _ => DEBUGGER_HELP.to_string(),
+        };
+        Some(output)
+    }
+
+    fn run_steps(&mut self, n: usize) -> String {
+        let lines: Vec<String> = (0..n)
+            .map_while(|_| self.step())
+            .map(|state| Debugger::describe(&state))
+            .collect();
+        if self.cpu.is_halted() {
+            format!("{}\nprogram ended", lines.join("\n"))
+                .trim_start()
+                .to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
+
+    fn run_to_breakpoint(&mut self) -> String {
+        while let Some(state) = self.step() {
+            if let Some(breakpoint) = self.breakpoints.iter().find(|b| b.hit(&state)) {
+                return format!("hit {}\n{}", breakpoint, Debugger::describe(&state));
             }
         }
+        "program ended".to_string()
     }
 
-    screen.iter().enumerate().for_each(|(n, pixel)| {
-        print!("{}", pixel);
-        if n % 40 == 39 {
-            println!()
+    fn add_breakpoint(&mut self, breakpoint: Option<Breakpoint>) -> String {
+        match breakpoint {
+            Some(breakpoint) => {
+                self.breakpoints.insert(breakpoint);
+                format!("breakpoint at {}", breakpoint)
+            }
+            None => "usage: break <cycle> | break line <n>".to_string(),
         }
-    });
+    }
 
-    None
+    /// Reads commands from stdin until `quit` or the end of the input.
+    fn run(&mut self) -> io::Result<()> {
+        println!("{}", DEBUGGER_HELP);
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        loop {
+            print!("(cpu) ");
+            io::stdout().flush()?;
+            let Some(command) = lines.next().transpose()? else {
+                return Ok(());
+            };
+            match self.execute(&command) {
+                Some(output) => println!("{}", output),
+                None => return Ok(()),
+            }
+        }
+    }
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_10;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(10, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 10);
+    advent_of_code::fingerprint::check(10, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+
+    if pico_args::Arguments::from_env().contains("--debug") {
+        match parse_program(input) {
+            Ok(program) => {
+                if let Err(e) = Debugger::new(&program).run() {
+                    eprintln!("{}", e);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 10);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(10, part_one, part_two);
+    }
+
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(
+            parse_program("noop\naddx -5\n"),
+            Ok(vec![Instruction::Noop, Instruction::Addx(-5)])
+        );
+        assert_eq!(
+            parse_program("noop\nmulx 3\n").unwrap_err().to_string(),
+            "line 2: unknown instruction \"mulx 3\""
+        );
+        assert_eq!(
+            parse_program("addx\n").unwrap_err().to_string(),
+            "line 1: invalid argument \"addx\""
+        );
+    }
+
+    #[test]
+    fn test_cpu() {
+        let program = parse_program("noop\naddx 3\naddx -5\n").unwrap();
+        let states: Vec<(u32, i32)> = Cpu::new(&program)
+            .map(|state| (state.cycle, state.x))
+            .collect();
+        assert_eq!(states, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn test_debugger() {
+        let program = parse_program("noop\naddx 3\naddx -5\n").unwrap();
+        let mut debugger = Debugger::new(&program);
+        assert_eq!(
+            debugger.execute("step").unwrap(),
+            "cycle   1  X=1   line   1: noop"
+        );
+        debugger.execute("break line 3");
+        assert_eq!(
+            debugger.execute("continue").unwrap(),
+            "hit line 3\ncycle   4  X=4   line   3: addx -5"
+        );
+        assert_eq!(
+            debugger.execute("step 5").unwrap(),
+            "cycle   5  X=4   line   3: addx -5 (cont.)\nprogram ended"
+        );
+        assert_eq!(debugger.execute("quit"), None);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 10);
@@ -120,6 +450,28 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 10);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(
+            part_two(&input).as_deref(),
+            Some(concat!(
+                "##..##..##..##..##..##..##..##..##..##..\n",
+                "###...###...###...###...###...###...###.\n",
+                "####....####....####....####....####....\n",
+                "#####.....#####.....#####.....#####.....\n",
+                "######......######......######......####\n",
+                "#######.......#######.......#######....."
+            ))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let input = advent_of_code::read_file("examples", 10);
+        let program = parse_program(&input).unwrap();
+        let screen = render(Cpu::new(&program));
+        assert_eq!(
+            screen.lines().next(),
+            Some("##..##..##..##..##..##..##..##..##..##..")
+        );
+        assert_eq!(screen.lines().count(), 6);
     }
 }
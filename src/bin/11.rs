@@ -1,6 +1,41 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+use advent_of_code::{helpers::math::lcm, params::Param};
 
 use itertools::Itertools;
+use num::{BigUint, CheckedAdd, CheckedMul, Integer};
+
+const PART_ONE_ROUNDS: Param<u64> = Param::new("part_one_rounds", 20);
+const PART_TWO_ROUNDS: Param<u64> = Param::new("part_two_rounds", 10_000);
+const WORRY: Param<WorryLevels> = Param::new("worry", WorryLevels::Checked);
+
+/// How worry levels are stored. `big` doesn't keep part two's worry levels modulo the lcm of the
+/// divisors, which checks that the reduction doesn't change the answer. Worry levels grow fast
+/// without it, so only use it with a few rounds, e.g. `--param worry=big --param part_two_rounds=20`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorryLevels {
+    /// `u64`, failing on overflow.
+    Checked,
+    /// `BigUint`, without the modulo reduction.
+    Big,
+}
+
+impl FromStr for WorryLevels {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "checked" => Ok(WorryLevels::Checked),
+            "big" => Ok(WorryLevels::Big),
+            _ => Err(format!("expected checked or big, got \"{}\"", s)),
+        }
+    }
+}
+
+/// A worry level type. Operations are checked, as they overflow for `u64`.
+trait Worry: Clone + fmt::Display + From<u64> + Integer + CheckedAdd + CheckedMul {}
+
+impl<T> Worry for T where T: Clone + fmt::Display + From<u64> + Integer + CheckedAdd + CheckedMul {}
 
 enum Operation {
     Add(Term, Term),
@@ -13,37 +48,74 @@ enum Term {
 }
 
 impl Term {
-    fn evaluate(&self, old: &u64) -> u64 {
+    fn evaluate<W: Worry>(&self, old: &W) -> W {
         match self {
-            Term::Constant(c) => c.clone(),
+            Term::Constant(c) => W::from(*c),
             Term::Old => old.clone(),
         }
     }
 }
 
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::Constant(c) => write!(f, "{}", c),
+            Term::Old => write!(f, "old"),
+        }
+    }
+}
+
 impl Operation {
-    fn evaluate(&self, old: &u64) -> u64 {
+    /// The new worry level, `None` if it overflows.
+    fn evaluate<W: Worry>(&self, old: &W) -> Option<W> {
         match self {
-            Operation::Add(l, r) => l.evaluate(old) + r.evaluate(old),
-            Operation::Multiply(l, r) => l.evaluate(old) * r.evaluate(old),
+            Operation::Add(l, r) => l.evaluate(old).checked_add(&r.evaluate(old)),
+            Operation::Multiply(l, r) => l.evaluate(old).checked_mul(&r.evaluate(old)),
         }
     }
 }
 
-struct Monkey {
-    items: VecDeque<u64>,
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Add(l, r) => write!(f, "new = {} + {}", l, r),
+            Operation::Multiply(l, r) => write!(f, "new = {} * {}", l, r),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Overflow {
+    round: u64,
+    monkey: usize,
+    worry_level: String,
+    operation: String,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "round {}: monkey {} overflowed inspecting an item with worry level {} ({}), try --param worry=big",
+            self.round, self.monkey, self.worry_level, self.operation
+        )
+    }
+}
+
+struct Monkey<W> {
+    items: VecDeque<W>,
     operation: Operation,
     divisor: u64,
     receiver_idxs: (usize, usize),
     inspected_count: u64,
 }
 
-struct InspectionResult {
-    new_item: u64,
+struct InspectionResult<W> {
+    new_item: W,
     target_monkey: usize,
 }
 
-impl Monkey {
+impl<W: Worry> Monkey<W> {
     // Example input:
     //
     // Monkey 0:
@@ -62,7 +134,7 @@ impl Monkey {
             .unwrap()
             .split(", ")
         {
-            items.push_back(item.parse().unwrap());
+            items.push_back(W::from(item.parse::<u64>().unwrap()));
         }
         let mut operation_line = lines[2]
             .split("Operation: new = ")
@@ -114,33 +186,26 @@ impl Monkey {
         }
     }
 
-    fn inspect_item<R>(&mut self, item: u64, round_op: R) -> InspectionResult
+    /// Inspects `item`, `None` if its worry level overflows.
+    fn inspect_item<R>(&mut self, item: &W, relief: R) -> Option<InspectionResult<W>>
     where
-        R: Fn(u64) -> u64,
+        R: Fn(W) -> W,
     {
-        {
-            self.inspected_count += 1;
-            // println!("Monkey inspect an item with a worry level of {}.", item);
-            let new_item = self.operation.evaluate(&item);
-            // println!("Worry level is changed to {}.", new_item);
-            let boring_item = round_op(new_item);
-
-            // println!("Monkey gets bored with an item. Worry level is divided by 3 to {}.", lcm_item);
-            let target_monkey = if boring_item % self.divisor == 0 {
-                self.receiver_idxs.0
-            } else {
-                self.receiver_idxs.1
-            };
-            // println!("Item with worry level {} is thrown to monkey {}.", lcm_item, target_monkey);
-            InspectionResult {
-                new_item: boring_item,
-                target_monkey: target_monkey,
-            }
-        }
+        self.inspected_count += 1;
+        let new_item = relief(self.operation.evaluate(item)?);
+        let target_monkey = if (new_item.clone() % W::from(self.divisor)).is_zero() {
+            self.receiver_idxs.0
+        } else {
+            self.receiver_idxs.1
+        };
+        Some(InspectionResult {
+            new_item,
+            target_monkey,
+        })
     }
 }
 
-fn parse_input(input: &str) -> Vec<Monkey> {
+fn parse_input<W: Worry>(input: &str) -> Vec<Monkey<W>> {
     input
         .split('\n')
         .chunks(7)
@@ -149,62 +214,79 @@ fn parse_input(input: &str) -> Vec<Monkey> {
         .collect()
 }
 
-// fn print_monkey_items(monkeys: &Vec<Monkey>) {
-//     monkeys.iter().enumerate().for_each(|(i, monkey)| {
-//         println!("Monkey {} has {:?} items.", i, monkey.items);
-//     });
-// }
-fn inspected_counts(monkeys: &Vec<Monkey>) -> Vec<u64> {
-    let inspected_counts: Vec<u64> = monkeys
-        .iter()
-        .map(|monkey| monkey.inspected_count)
-        .collect();
-    // inspected_counts.iter().enumerate().for_each(|(i, count)| {
-    //     println!("Monkey {} inspected items {} times.", i, count);
-    // });
-    inspected_counts
-}
-
-fn solve_input<R>(mut monkeys: Vec<Monkey>, rounds: u64, round_op: R) -> Option<u64>
+fn simulate<W, R>(mut monkeys: Vec<Monkey<W>>, rounds: u64, relief: R) -> Result<u64, Overflow>
 where
-    R: Fn(u64) -> u64,
+    W: Worry,
+    R: Fn(W) -> W,
 {
-    for _ in 0..rounds {
+    for round in 1..=rounds {
         for i in 0..monkeys.len() {
             while let Some(item) = monkeys[i].items.pop_front() {
-                let res = monkeys[i].inspect_item(item, &round_op);
+                let res = monkeys[i]
+                    .inspect_item(&item, &relief)
+                    .ok_or_else(|| Overflow {
+                        round,
+                        monkey: i,
+                        worry_level: item.to_string(),
+                        operation: monkeys[i].operation.to_string(),
+                    })?;
                 monkeys[res.target_monkey].items.push_back(res.new_item);
             }
         }
-
-        // print_monkey_items(&monkeys);
-        // if round % 1000 == 999 {
-        //     println!("Round {}.", round);
-        //     inspected_counts(&monkeys);
-        // }
     }
 
-    let inspected_counts = inspected_counts(&monkeys);
     // Return multiple of the two most inspected monkeys.
-    Some(inspected_counts.iter().sorted().rev().take(2).product())
+    Ok(monkeys
+        .iter()
+        .map(|monkey| monkey.inspected_count)
+        .sorted()
+        .rev()
+        .take(2)
+        .product())
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let monkeys = parse_input(input);
-    solve_input(monkeys, 20, |x: u64| x / 3u64)
+fn part_two_with<W: Worry>(input: &str, rounds: u64, worry_reduction: bool) -> u64 {
+    let monkeys: Vec<Monkey<W>> = parse_input(input);
+    let result = if worry_reduction {
+        // every test only depends on the worry level modulo the monkey's divisor, so keeping it
+        // modulo the lcm of all divisors doesn't change where items are thrown.
+        let divisors: Vec<u64> = monkeys.iter().map(|monkey| monkey.divisor).collect();
+        let lcm = W::from(lcm(&divisors));
+        simulate(monkeys, rounds, |x: W| x % lcm.clone())
+    } else {
+        simulate(monkeys, rounds, |x| x)
+    };
+    result.unwrap_or_else(|e| panic!("{}", e))
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let monkeys = parse_input(input);
+fn part_one_with<W: Worry>(input: &str) -> u64 {
+    let monkeys: Vec<Monkey<W>> = parse_input(input);
+    let three = W::from(3);
+    simulate(monkeys, PART_ONE_ROUNDS.get(), |x: W| x / three.clone())
+        .unwrap_or_else(|e| panic!("{}", e))
+}
 
-    // lcm of all monkeys divisors
-    let lcm: u64 = monkeys.iter().map(|monkey| monkey.divisor).product();
+pub fn part_one(input: &str) -> Option<u64> {
+    match WORRY.get() {
+        WorryLevels::Checked => Some(part_one_with::<u64>(input)),
+        WorryLevels::Big => Some(part_one_with::<BigUint>(input)),
+    }
+}
 
-    solve_input(monkeys, 10000, |x: u64| x % lcm)
+pub fn part_two(input: &str) -> Option<u64> {
+    let rounds = PART_TWO_ROUNDS.get();
+    match WORRY.get() {
+        WorryLevels::Checked => Some(part_two_with::<u64>(input, rounds, true)),
+        WorryLevels::Big => Some(part_two_with::<BigUint>(input, rounds, false)),
+    }
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_11;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(11, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 11);
+    advent_of_code::fingerprint::check(11, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -212,6 +294,60 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::params;
+
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 11);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(11, part_one, part_two);
+    }
+
+    #[test]
+    fn test_big_worry_levels() {
+        let input = advent_of_code::read_file("examples", 11);
+        let (checked, big) = params::with_overrides([("part_two_rounds", 10)], || {
+            let checked = part_two(&input);
+            (
+                checked,
+                params::with_overrides([("worry", "big")], || part_two(&input)),
+            )
+        });
+        assert_eq!(checked, big);
+        assert_eq!(
+            params::with_overrides([("worry", "big")], || part_one(&input)),
+            Some(10605)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "Monkey 0:
+  Starting items: 4294967296, 2
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 1
+  Operation: new = old + 0
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let monkeys: Vec<Monkey<u64>> = parse_input(input);
+        assert_eq!(
+            simulate(monkeys, 1, |x| x).unwrap_err().to_string(),
+            "round 1: monkey 0 overflowed inspecting an item with worry level 4294967296 (new = old * old), try --param worry=big"
+        );
+        let monkeys: Vec<Monkey<BigUint>> = parse_input(input);
+        assert_eq!(simulate(monkeys, 1, |x| x), Ok(6));
+    }
 
     #[test]
     fn test_part_one() {
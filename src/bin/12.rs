@@ -1,329 +1,130 @@
-use std::{collections::VecDeque, fmt};
+use advent_of_code::helpers::{
+    grid::{Grid, Pos},
+    point::{Direction, Point2},
+    search::bfs,
+};
+use std::fmt;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+enum Cell {
+    Step(Direction),
     End,
     Start,
     Unvisited,
 }
 
-impl Direction {
+impl Cell {
     fn to_char(&self) -> char {
         match self {
-            Direction::Up => '╵',
-            Direction::Down => '╷',
-            Direction::Left => '╴',
-            Direction::Right => '╶',
-            Direction::End => 'E',
-            Direction::Start => 'S',
-            Direction::Unvisited => '.',
+            Cell::Step(Direction::Up) => '╵',
+            Cell::Step(Direction::Down) => '╷',
+            Cell::Step(Direction::Left) => '╴',
+            Cell::Step(Direction::Right) => '╶',
+            Cell::End => 'E',
+            Cell::Start => 'S',
+            Cell::Unvisited => '.',
         }
     }
 }
 
-impl fmt::Display for Direction {
+impl fmt::Display for Cell {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.to_char())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl Coord {
-    fn direction(&self, other: &Coord) -> Direction {
-        if self.x as i32 - other.x as i32 == 1 {
-            Direction::Left
-        } else if self.x as i32 - other.x as i32 == -1 {
-            Direction::Right
-        } else if self.y as i32 - other.y as i32 == 1 {
-            Direction::Up
-        } else {
-            Direction::Down
-        }
-    }
-
-    fn estimated_distance(&self, other: &Coord) -> u32 {
-        (self.x.abs_diff(other.x) + self.y.abs_diff(other.y)) as u32
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Node {
-    coord: Coord,
-    elevation: u32,
-    prev: Option<Coord>,
-    distance_from_end: u32,
-    distance_to_end_heuristic: u32,
-    visited: bool,
-}
-
-impl Node {
-    fn start_node(coord: Coord) -> Node {
-        Node {
-            coord,
-            elevation: 1,
-            prev: None,
-            distance_from_end: u32::MAX,
-            distance_to_end_heuristic: 0,
-            visited: false,
-        }
-    }
-
-    fn end_node(coord: Coord) -> Node {
-        Node {
-            coord,
-            elevation: 27,
-            prev: None,
-            distance_from_end: 0,
-            distance_to_end_heuristic: 0,
-            visited: false,
-        }
-    }
-
-    fn new(coord: Coord, elevation: u32) -> Node {
-        Node {
-            coord,
-            elevation,
-            prev: None,
-            distance_from_end: u32::MAX,
-            distance_to_end_heuristic: 0,
-            visited: false,
-        }
-    }
-
-    fn elevation_passable(&self, neighbor: &Node) -> bool {
-        neighbor.elevation >= (self.elevation - 1)
-    }
+/// Direction of the step from `from` to its neighbour `to`.
+fn direction(from: Pos, to: Pos) -> Direction {
+    Direction::from_offset(Point2::from(to) - Point2::from(from)).expect("not a neighbour")
 }
 
 struct Input {
-    map: Vec<Vec<Node>>,
-    start: Coord,
-    end: Coord,
-    height: usize,
-    width: usize,
+    elevations: Grid<u32>,
+    start: Pos,
+    end: Pos,
 }
 
 impl Input {
-    fn node(&self, coord: &Coord) -> &Node {
-        &self.map[coord.y][coord.x]
-    }
-
-    #[allow(dead_code)]
-    fn estimated_distance(&self, coord: &Coord) -> u32 {
-        let node = &self.map[coord.y][coord.x];
-        let end_node = &self.map[self.end.y][self.end.x];
-        let g = node.distance_from_end;
-        let h = node.coord.estimated_distance(&end_node.coord);
-        g + h
-    }
-
-    fn shortest_path(&mut self, first_part: bool) -> Vec<Coord> {
-        // BFS
-        let mut open: VecDeque<Coord> = VecDeque::new();
-        open.push_back(self.end);
-
-        while let Some(current) = open.pop_front() {
-            // TODO optimize
-            // let min_index = (0..(open.len()))
-            //     .map(|i| (i, self.estimated_distance(&open[i])))
-            //     .min_by_key(|(_, d)| *d)
-            //     .unwrap()
-            //     .0;
-
-            let current_distance = self.node(&current).distance_from_end;
-
-            {
-                let mut node = &mut self.map[current.y][current.x];
-                node.visited = true;
-
-                if (first_part && current == self.start)
-                    || (!first_part && node.elevation == 1)
-                {
-                    return self.get_path(current);
+    /// Squares that can be reached from `pos`, searching backwards from the end: a step may go at
+    /// most one higher going forward, so backwards it may go at most one lower.
+    fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let elevation = self.elevations[pos];
+        self.elevations
+            .neighbours4(pos)
+            .filter(move |neighbor| self.elevations[*neighbor] + 1 >= elevation)
+    }
+
+    /// Shortest path from the start (part one) or any lowest square (part two) to the end, `None`
+    /// if the end can't be reached.
+    fn shortest_path(&self, first_part: bool) -> Option<Vec<Pos>> {
+        // searching from the end finds the closest lowest square for part two in one pass.
+        let mut nodes = bfs(
+            self.end,
+            |pos| self.neighbors(*pos),
+            |pos| {
+                if first_part {
+                    *pos == self.start
+                } else {
+                    self.elevations[*pos] == 1
                 }
-               
-            }
-
-            let neighbors = self.neighbors(&current);
-            for neighbor in neighbors {
-                let neighbor_node = &mut self.map[neighbor.y][neighbor.x];
-                if neighbor_node.distance_from_end > current_distance + 1 {
-                    neighbor_node.distance_from_end = current_distance + 1;
-                    neighbor_node.prev = Some(current);
-                    neighbor_node.visited = true;
-                    open.push_back(neighbor);
-                }
-            }
-
-            // self.print_path(current);
-            // self.print_visited_or_open(&open);
-        }
-        Vec::new()
+            },
+        )?
+        .nodes;
+        nodes.reverse();
+        Some(nodes)
     }
 
-    fn neighbors(&self, coord: &Coord) -> Vec<Coord> {
-        let node = &self.map[coord.y][coord.x];
-        let mut neighbors: Vec<Coord> = Vec::new();
-        if coord.y > 0 {
-            let neighbor = &self.map[coord.y - 1][coord.x];
-            if node.elevation_passable(&neighbor) {
-                neighbors.push(Coord {
-                    x: coord.x,
-                    y: coord.y - 1,
-                });
-            }
-        }
-        if coord.y < self.height - 1 {
-            let neighbor = &self.map[coord.y + 1][coord.x];
-            if node.elevation_passable(&neighbor) {
-                neighbors.push(Coord {
-                    x: coord.x,
-                    y: coord.y + 1,
-                });
-            }
-        }
-        if coord.x > 0 {
-            let neighbor = &self.map[coord.y][coord.x - 1];
-            if node.elevation_passable(&neighbor) {
-                neighbors.push(Coord {
-                    x: coord.x - 1,
-                    y: coord.y,
-                });
-            }
-        }
-        if coord.x < self.width - 1 {
-            let neighbor = &self.map[coord.y][coord.x + 1];
-            if node.elevation_passable(&neighbor) {
-                neighbors.push(Coord {
-                    x: coord.x + 1,
-                    y: coord.y,
-                });
-            }
-        }
+    fn render_path(&self, path: &[Pos]) -> String {
+        let mut cells = self.elevations.map(|_| Cell::Unvisited);
 
-        neighbors
-    }
-
-    fn get_path(&self, target_coord: Coord) -> Vec<Coord> {
-        let mut path: Vec<Coord> = Vec::new();
-        let mut current: Option<Coord> = Some(target_coord);
-
-        while current.is_some() {
-            let prev_node = self.node(&current.unwrap());
-            if let Some(prev) = prev_node.prev {
-                path.push(prev);
-            }
-            current = prev_node.prev;
+        for w in path.windows(2) {
+            cells[w[0]] = Cell::Step(direction(w[0], w[1]));
         }
-        path
-    }
-
-    fn print_path(&self, path: &Vec<Coord>) {
-        let mut directions: Vec<Vec<Direction>> =
-            vec![vec![Direction::Unvisited; self.width]; self.height];
-
-        let end_coord = path.last().unwrap();
-        directions[end_coord.y][end_coord.x] = Direction::End;
-        let start_coord = path.first().unwrap();
-        directions[start_coord.y][start_coord.x] = Direction::Start;
-
-        for w in path[..].windows(2) {
-            let current = w[0];
-            let prev = w[1];
-            let direction = current.direction(&prev);
-            directions[current.y][current.x] = direction;
+        if let (Some(first), Some(last)) = (path.first(), path.last()) {
+            cells[*first] = Cell::Start;
+            cells[*last] = Cell::End;
         }
 
-        // print directions
-        for row in directions {
-            for direction in row {
-                print!("{}", direction);
-            }
-            println!();
-        }
-        println!();
+        cells.render(Cell::to_char)
     }
 }
 
 fn parse_input(input: &str) -> Input {
-    let mut map: Vec<Vec<Node>> = Vec::new();
-    let mut start: Coord = Coord { x: 0, y: 0 };
-    let mut end: Coord = Coord { x: 0, y: 0 };
-
-    input.lines().for_each(|line| {
-        let mut row: Vec<Node> = Vec::new();
-        line.chars().for_each(|c| {
-            if c == 'S' {
-                let start_coord = Coord {
-                    x: row.len(),
-                    y: map.len(),
-                };
-                let node = Node::start_node(start_coord);
-                start = node.coord;
-                row.push(node);
-            } else if c == 'E' {
-                let end_coord = Coord {
-                    x: row.len(),
-                    y: map.len(),
-                };
-                let node = Node::end_node(end_coord);
-                end = end_coord;
-                row.push(node);
-            } else {
-                let coord = Node::new(
-                    Coord {
-                        x: row.len(),
-                        y: map.len(),
-                    },
-                    c as u32 - 'a' as u32 + 1,
-                );
-                row.push(coord);
-            }
-        });
-        map.push(row);
+    let chars = Grid::parse(input, Some).unwrap_or_else(|e| panic!("{}", e));
+    let find = |target: char| chars.iter().find(|(_, c)| **c == target).unwrap().0;
+    let elevations = chars.map(|c| match c {
+        'S' => 1,
+        'E' => 26,
+        c => *c as u32 - 'a' as u32 + 1,
     });
-    let height = map.len();
-    let width = map[0].len();
-
-    // Update estimated distance to end
-    for mut node in map.iter_mut().flatten() {
-        node.distance_to_end_heuristic = node.coord.estimated_distance(&end);
-    }
 
     Input {
-        map,
-        start,
-        end,
-        height,
-        width,
+        elevations,
+        start: find('S'),
+        end: find('E'),
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut input = parse_input(input);
-    let shortest_path = input.shortest_path(true);
-    input.print_path(&shortest_path);
-    Some(shortest_path.len() as u32)
+    let input = parse_input(input);
+    let shortest_path = input.shortest_path(true)?;
+    advent_of_code::debug!("shortest path:\n{}", input.render_path(&shortest_path));
+    Some(shortest_path.len() as u32 - 1)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut input = parse_input(input);
-    let shortest_path = input.shortest_path(false); 
-    input.print_path(&shortest_path);
-    Some(shortest_path.len() as u32)
+    let input = parse_input(input);
+    let shortest_path = input.shortest_path(false)?;
+    advent_of_code::debug!("shortest path:\n{}", input.render_path(&shortest_path));
+    Some(shortest_path.len() as u32 - 1)
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_12;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(12, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 12);
+    advent_of_code::fingerprint::check(12, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -332,6 +133,17 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 12);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(12, part_one, part_two);
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 12);
@@ -343,4 +155,10 @@ mod tests {
         let input = advent_of_code::read_file("examples", 12);
         assert_eq!(part_two(&input), Some(29));
     }
+
+    #[test]
+    fn test_unreachable_end() {
+        assert_eq!(part_one("SbE\n"), None);
+        assert_eq!(part_two("SbE\n"), None);
+    }
 }
@@ -1,233 +1,368 @@
+use advent_of_code::helpers::parse::{groups, parse_all, unsigned, ParseError};
 use nom::{
     branch::alt,
-    character::complete::{char, digit1, newline},
+    character::complete::{char, line_ending, multispace0, space0},
+    combinator::map,
     multi::separated_list0,
-    sequence::terminated,
+    sequence::{delimited, preceded, separated_pair, terminated},
     IResult,
 };
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// A packet or a value inside one. Serializes to and from the JSON arrays packets are written as.
+/// `Ord` and `Eq` follow the puzzle's ordering, so `2 == [2]`. Use `Packet::is_identical` to
+/// compare the structure of packets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Packet {
+    Number(u32),
+    List(Vec<Packet>),
+}
 
-#[derive(Debug)]
-struct Pair {
-    left: List,
-    right: List,
+/// Builds a packet from a literal, e.g. `packet![1, [2, 3], []]` for `[1,[2,3],[]]`.
+macro_rules! packet {
+    (@item [$($item:tt),* $(,)?]) => {
+        Packet::List(vec![$(packet!(@item $item)),*])
+    };
+    (@item $number:expr) => {
+        Packet::Number($number)
+    };
+    ($($item:tt),* $(,)?) => {
+        Packet::List(vec![$(packet!(@item $item)),*])
+    };
 }
 
-impl std::fmt::Display for Pair {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}\n{}\n\n", self.left, self.right)
+/// Packets are ordered by the rules of the puzzle: numbers by value, lists element by element
+/// and a number compared to a list as if it was a list of one number.
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other, 0, &mut ())
     }
 }
 
-#[derive(Debug)]
-struct Pairs {
-    pairs: Vec<Pair>,
+impl Packet {
+    /// Whether both packets are written the same way, unlike `==` which treats `2` as `[2]`.
+    fn is_identical(&self, other: &Packet) -> bool {
+        match (self, other) {
+            (Packet::Number(left), Packet::Number(right)) => left == right,
+            (Packet::List(left), Packet::List(right)) => {
+                left.len() == right.len() && left.iter().zip(right).all(|(l, r)| l.is_identical(r))
+            }
+            _ => false,
+        }
+    }
+
+    /// Compares packets, reporting every step to `observer`. `depth` is the nesting of the
+    /// comparison, 0 for the packets of a pair.
+    fn compare(&self, other: &Packet, depth: usize, observer: &mut impl Observer) -> Ordering {
+        observer.compare(depth, self, other);
+        match (self, other) {
+            (Packet::Number(left), Packet::Number(right)) => {
+                let ordering = left.cmp(right);
+                match ordering {
+                    Ordering::Less => observer.decide(depth + 1, Outcome::LeftSmaller),
+                    Ordering::Greater => observer.decide(depth + 1, Outcome::RightSmaller),
+                    Ordering::Equal => {}
+                }
+                ordering
+            }
+            (Packet::List(left), Packet::List(right)) => {
+                compare_items(left, right, depth + 1, observer)
+            }
+            (Packet::Number(_), Packet::List(right)) => {
+                let left = std::slice::from_ref(self);
+                observer.convert(depth + 1, Side::Left, &Items(left));
+                observer.compare(depth + 1, &Items(left), other);
+                compare_items(left, right, depth + 2, observer)
+            }
+            (Packet::List(left), Packet::Number(_)) => {
+                let right = std::slice::from_ref(other);
+                observer.convert(depth + 1, Side::Right, &Items(right));
+                observer.compare(depth + 1, self, &Items(right));
+                compare_items(left, right, depth + 2, observer)
+            }
+        }
+    }
 }
 
-impl std::fmt::Display for Pairs {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for pair in self.pairs.iter() {
-            write!(f, "{}", pair)?;
+/// Compares the items of two lists until one differs or a list runs out.
+fn compare_items(
+    left: &[Packet],
+    right: &[Packet],
+    depth: usize,
+    observer: &mut impl Observer,
+) -> Ordering {
+    for (l, r) in left.iter().zip(right) {
+        let ordering = l.compare(r, depth, observer);
+        if ordering.is_ne() {
+            return ordering;
         }
-        Ok(())
     }
+    let ordering = left.len().cmp(&right.len());
+    match ordering {
+        Ordering::Less => observer.decide(depth, Outcome::LeftRanOut),
+        Ordering::Greater => observer.decide(depth, Outcome::RightRanOut),
+        Ordering::Equal => {}
+    }
+    ordering
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct List {
-    elements: Vec<Listable>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
 }
 
-impl std::fmt::Display for List {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[")?;
-        for (i, element) in self.elements.iter().enumerate() {
-            if i > 0 {
-                write!(f, ",")?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    LeftSmaller,
+    RightSmaller,
+    LeftRanOut,
+    RightRanOut,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::LeftSmaller => write!(f, "Left side is smaller"),
+            Outcome::RightSmaller => write!(f, "Right side is smaller"),
+            Outcome::LeftRanOut => write!(f, "Left side ran out of items"),
+            Outcome::RightRanOut => write!(f, "Right side ran out of items"),
+        }?;
+        match self {
+            Outcome::LeftSmaller | Outcome::LeftRanOut => {
+                write!(f, ", so inputs are in the right order")
+            }
+            Outcome::RightSmaller | Outcome::RightRanOut => {
+                write!(f, ", so inputs are not in the right order")
             }
-            write!(f, "{}", element)?;
         }
-        write!(f, "]")
     }
 }
 
-impl List {
-    fn compare(&self, other: &List) -> Option<bool> {
-        let mut left_iter = self.elements.iter();
-        let mut right_iter = other.elements.iter();
-        loop {
-            let (left, right) = (left_iter.next(), right_iter.next());
-            match (left, right) {
-                (None, Some(_)) => {
-                    return Some(true);
-                },
-                (Some(_), None) => {
-                    return Some(false);
-                },
-                (Some(left), Some(right)) => {
-                    let res = left.compare(right);
-                    if res.is_some() {
-                        return res;
-                    }
-                },
-                (None, None) => {
-                    return None
-                },
-            }
-        }
+/// Steps of a comparison, `depth` is the nesting of the step.
+trait Observer {
+    fn compare(&mut self, depth: usize, left: &dyn fmt::Display, right: &dyn fmt::Display);
+    fn convert(&mut self, depth: usize, side: Side, converted: &dyn fmt::Display);
+    fn decide(&mut self, depth: usize, outcome: Outcome);
+}
+
+/// Ignores all steps, for plain comparisons.
+impl Observer for () {
+    fn compare(&mut self, _: usize, _: &dyn fmt::Display, _: &dyn fmt::Display) {}
+    fn convert(&mut self, _: usize, _: Side, _: &dyn fmt::Display) {}
+    fn decide(&mut self, _: usize, _: Outcome) {}
+}
+
+/// Writes the steps like the walkthrough in the puzzle description.
+#[derive(Debug, Default)]
+struct Explanation {
+    lines: Vec<String>,
+}
+
+impl Explanation {
+    fn push(&mut self, depth: usize, line: String) {
+        self.lines.push(format!("{}- {}", "  ".repeat(depth), line));
+    }
+}
+
+impl Observer for Explanation {
+    fn compare(&mut self, depth: usize, left: &dyn fmt::Display, right: &dyn fmt::Display) {
+        self.push(depth, format!("Compare {} vs {}", left, right));
     }
 
-    fn from_number(number: &u32) -> List {
-        List {
-            elements: vec![Listable::Number(*number)],
-        }
+    fn convert(&mut self, depth: usize, side: Side, converted: &dyn fmt::Display) {
+        let side = match side {
+            Side::Left => "left",
+            Side::Right => "right",
+        };
+        self.push(
+            depth,
+            format!(
+                "Mixed types; convert {} to {} and retry comparison",
+                side, converted
+            ),
+        );
+    }
+
+    fn decide(&mut self, depth: usize, outcome: Outcome) {
+        self.push(depth, outcome.to_string());
     }
 }
 
-impl PartialOrd for List {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.compare(other) {
-            Some(true) => Some(std::cmp::Ordering::Less),
-            Some(false) => Some(std::cmp::Ordering::Greater),
-            None => Some(std::cmp::Ordering::Equal),
+/// Items of a list, displayed as the list.
+struct Items<'a>(&'a [Packet]);
+
+impl fmt::Display for Items<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", item)?;
         }
+        write!(f, "]")
     }
 }
 
-impl Ord for List {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-fn print_vec_list(list: &Vec<List>) {
-    for list in list {
-        println!("{}", list);
+/// Equality follows the ordering, so `2` equals `[2]`. See `Packet::is_identical` for structural
+/// equality.
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Listable {
-    List(List),
-    Number(u32),
-}
+impl Eq for Packet {}
 
-impl std::fmt::Display for Listable {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Listable::List(list) => write!(f, "{}", list),
-            Listable::Number(number) => write!(f, "{}", number),
+            Packet::Number(number) => write!(f, "{}", number),
+            Packet::List(items) => write!(f, "{}", Items(items)),
         }
     }
 }
 
-impl Listable {
-    fn compare(&self, other: &Self) -> Option<bool> {
-        match (self, other) {
-            (Listable::Number(left), Listable::Number(right)) => {
-                if left < right {
-                    Some(true)
-                } else if left > right {
-                    Some(false)
-                } else {
-                    None
-                }
-            },
-            (Listable::List(left), Listable::List(right)) => {
-                left.compare(right)
-            },
-            (Listable::List(left), Listable::Number(right)) => {
-                left.compare(&List::from_number(right))
-            },
-            (Listable::Number(left), Listable::List(right)) => {
-                List::from_number(left).compare(right)
-            },
-        }
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_all(delimited(multispace0, parse_packet, multispace0), s)
     }
 }
 
-impl Pair {
-    fn order_check(&self) -> bool {
-        self.left.compare(&self.right).unwrap()
+impl TryFrom<serde_json::Value> for Packet {
+    type Error = serde_json::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value)
+    }
+}
+
+impl From<&Packet> for serde_json::Value {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Number(number) => serde_json::Value::from(*number),
+            Packet::List(items) => items.iter().map(serde_json::Value::from).collect(),
+        }
     }
 }
 
-fn parse_number(input: &str) -> IResult<&str, Listable> {
-    let (input, number) = digit1(input)?;
-    let number = Listable::Number(number.parse().unwrap());
-    Ok((input, number))
+#[derive(Debug)]
+struct Pair {
+    left: Packet,
+    right: Packet,
 }
 
-fn parse_listable(input: &str) -> IResult<&str, Listable> {
-    let (input, listable) = alt((parse_list_as_listable, parse_number))(input)?;
-    Ok((input, listable))
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.left, self.right)
+    }
 }
 
-fn parse_list(input: &str) -> IResult<&str, List> {
-    let (input, _) = char('[')(input)?;
-    let (input, elements) = separated_list0(char(','), parse_listable)(input)?;
-    let (input, _) = char(']')(input)?;
-    Ok((input, List { elements }))
+impl Pair {
+    fn in_order(&self) -> bool {
+        self.left < self.right
+    }
+
+    /// The steps of comparing the packets, from the same comparison as `in_order`.
+    fn explain(&self) -> String {
+        let mut explanation = Explanation::default();
+        if self.left.compare(&self.right, 0, &mut explanation).is_eq() {
+            let reason = if self.left.is_identical(&self.right) {
+                "Packets are identical, neither side is smaller"
+            } else {
+                "Packets only differ in mixed types, neither side is smaller"
+            };
+            explanation.push(1, reason.to_string());
+        }
+        explanation.lines.join("\n")
+    }
 }
 
-fn parse_list_as_listable(input: &str) -> IResult<&str, Listable> {
-    let (input, list) = parse_list(input)?;
-    let list = Listable::List(list);
-    Ok((input, list))
+/// Parses a packet. Whitespace is allowed around numbers and brackets.
+fn parse_packet(input: &str) -> IResult<&str, Packet> {
+    alt((
+        map(unsigned, Packet::Number),
+        map(
+            delimited(
+                terminated(char('['), multispace0),
+                separated_list0(delimited(multispace0, char(','), multispace0), parse_packet),
+                preceded(multispace0, char(']')),
+            ),
+            Packet::List,
+        ),
+    ))(input)
 }
 
 fn parse_pair(input: &str) -> IResult<&str, Pair> {
-    // let (input, pair) = pair(, terminated(parse_list, newline))?;
-    let (input, first) = terminated(parse_list, newline)(input)?;
-    let (input, second) = terminated(parse_list, newline)(input)?;
-    let pair = Pair { left: first, right: second };
-    Ok((input, pair))
+    let line = || delimited(space0, parse_packet, space0);
+    let (input, (left, right)) = separated_pair(line(), line_ending, line())(input)?;
+    Ok((input, Pair { left, right }))
 }
 
-fn parse_input(input: &str) -> Pairs {
-    let (input, pairs) = separated_list0(newline, parse_pair)(input).unwrap();
-    if input.len() > 0 {
-        panic!("Failed to parse all input");
-    }
-    Pairs{pairs}
+fn parse_input(input: &str) -> Vec<Pair> {
+    parse_all(groups(parse_pair), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let pairs = parse_input(input);
-    println!("{}", pairs);
-    let comparisons: Vec<bool> = pairs.pairs.iter().map(|pair| pair.order_check()).collect();
-    println!("{:?}", comparisons);
-    Some(comparisons.into_iter().enumerate().filter_map(|(i, c)| {
-        if c {
-            Some(i as u32 + 1)
-        } else {
-            None
-        }
-    }).sum())
+    advent_of_code::trace!(
+        "pairs:\n{}",
+        pairs
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    );
+    let in_order: Vec<bool> = pairs.iter().map(Pair::in_order).collect();
+    advent_of_code::debug!("in order: {:?}", in_order);
+    let indices = in_order
+        .into_iter()
+        .enumerate()
+        .filter(|(_, in_order)| *in_order)
+        .map(|(i, _)| i as u32 + 1);
+    Some(indices.sum())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let pairs = parse_input(input);
-    println!("{}", pairs);
-    let mut packets = pairs.pairs.into_iter().map(|pair| vec![pair.left, pair.right]).flatten().collect::<Vec<_>>();
-    let additional_packet_1 = parse_list("[[2]]").unwrap().1;
-    let additional_packet_2 = parse_list("[[6]]").unwrap().1;
-    
-    packets.push(additional_packet_1.clone());
-    packets.push(additional_packet_2.clone());
-
-    packets.sort();
-    print_vec_list(&packets);
-
-    let index_of_additional_packet_1 = packets.iter().position(|p| p == &additional_packet_1).unwrap() as u32 + 1;
-    let index_of_additional_packet_2 = packets.iter().position(|p| p == &additional_packet_2).unwrap() as u32 + 1;
+    let packets: Vec<Packet> = parse_input(input)
+        .into_iter()
+        .flat_map(|pair| [pair.left, pair.right])
+        .collect();
+    // The position of a divider in the sorted packets is one more than the number of packets
+    // before it, the second divider also comes after the first one.
+    let position = |divider: &Packet| packets.iter().filter(|p| *p < divider).count() as u32 + 1;
+    Some(position(&packet![[2]]) * (position(&packet![[6]]) + 1))
+}
 
-    Some(index_of_additional_packet_1 * index_of_additional_packet_2)
+fn explain(input: &str) -> String {
+    parse_input(input)
+        .iter()
+        .enumerate()
+        .map(|(i, pair)| format!("== Pair {} ==\n{}", i + 1, pair.explain()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_13;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(13, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 13);
+    advent_of_code::fingerprint::check(13, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::explain::explain_from_args(|| explain(input));
 }
 
 #[cfg(test)]
@@ -235,77 +370,144 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_number() {
-        let input = "123";
-        let (input, number) = parse_number(input).unwrap();
-        assert_eq!(input, "");
-        assert_eq!(number, Listable::Number(123));
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 13);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(13, part_one, part_two);
     }
 
     #[test]
-    fn test_parse_list() {
-        let input = "[]";
-        let (input, list) = parse_list(input).unwrap();
-        assert_eq!(input, "");
-        assert_eq!(list, List { elements: vec![] });
-
-        let input = "[1,2,3]";
-        let (input, list) = parse_list(input).unwrap();
-        assert_eq!(input, "");
+    fn test_parse_packet() {
+        assert_eq!(parse_packet("123").unwrap().1.to_string(), "123");
+        assert_eq!(parse_packet("[]").unwrap().1.to_string(), "[]");
+        let packet: Packet = " [ 1,[2 , 3],\n  [] ] ".parse().unwrap();
+        assert_eq!(packet.to_string(), "[1,[2,3],[]]");
         assert_eq!(
-            list,
-            List {
-                elements: vec![
-                    Listable::Number(1),
-                    Listable::Number(2),
-                    Listable::Number(3)
-                ]
-            }
+            "[1,[2,3]".parse::<Packet>().unwrap_err().to_string(),
+            "parse error at line 1, column 9: unexpected end of line"
         );
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(packet![].to_string(), "[]");
+        assert_eq!(packet![[2]].to_string(), "[[2]]");
+        assert_eq!(packet![1, [2, [3, 4]], []].to_string(), "[1,[2,[3,4]],[]]");
+    }
 
-        let input = "[1,[2,3]]";
-        let (input, list) = parse_list(input).unwrap();
-        assert_eq!(input, "");
+    #[test]
+    fn test_ordering() {
+        assert!(packet![1, 1, 3, 1, 1] < packet![1, 1, 5, 1, 1]);
+        assert!(packet![[1], [2, 3, 4]] < packet![[1], 4]);
+        assert!(packet![9] > packet![[8, 7, 6]]);
+        assert!(packet![[4, 4], 4, 4] < packet![[4, 4], 4, 4, 4]);
+        assert!(packet![[[]]] > packet![[]]);
+        assert_eq!(packet![[2]], packet![2]);
+        assert_ne!(packet![2], packet![2, 2]);
+
+        let mut packets = vec![packet![[6]], packet![1, 2], packet![[2]], packet![]];
+        packets.sort();
         assert_eq!(
-            list,
-            List {
-                elements: vec![
-                    Listable::Number(1),
-                    Listable::List(List {
-                        elements: vec![Listable::Number(2), Listable::Number(3)]
-                    })
-                ]
-            }
+            packets,
+            vec![packet![], packet![1, 2], packet![[2]], packet![[6]]]
         );
     }
 
     #[test]
-    fn test_parse_list_as_listable() {
-        let input = "[1,[2,3]]";
-        let (input, list) = parse_list_as_listable(input).unwrap();
-        assert_eq!(input, "");
+    fn test_explain_equal_packets() {
+        let pair = Pair {
+            left: packet![[2]],
+            right: packet![2],
+        };
+        assert!(!pair.in_order());
         assert_eq!(
-            list,
-            Listable::List(List {
-                elements: vec![
-                    Listable::Number(1),
-                    Listable::List(List {
-                        elements: vec![Listable::Number(2), Listable::Number(3)]
-                    })
-                ]
-            })
+            pair.explain(),
+            "- Compare [[2]] vs [2]
+  - Compare [2] vs 2
+    - Mixed types; convert right to [2] and retry comparison
+    - Compare [2] vs [2]
+      - Compare 2 vs 2
+  - Packets only differ in mixed types, neither side is smaller"
         );
     }
 
     #[test]
-    fn test_list_equality() {
-        let list1 = parse_list("[[2]]").unwrap().1;
-        let list2 = List {
-            elements: vec![Listable::List(List {
-                elements: vec![Listable::Number(2)]
-            })]
-        };
-        assert_eq!(list1, list2);
+    fn test_is_identical() {
+        assert!(packet![1, [2]].is_identical(&packet![1, [2]]));
+        assert!(!packet![[2]].is_identical(&packet![2]));
+        assert!(!Packet::Number(2).is_identical(&packet![2]));
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = advent_of_code::read_file("examples", 13);
+        let explanation = explain(&input);
+        assert!(explanation.starts_with(
+            "== Pair 1 ==
+- Compare [1,1,3,1,1] vs [1,1,5,1,1]
+  - Compare 1 vs 1
+  - Compare 1 vs 1
+  - Compare 3 vs 5
+    - Left side is smaller, so inputs are in the right order
+
+== Pair 2 ==
+- Compare [[1],[2,3,4]] vs [[1],4]
+  - Compare [1] vs [1]
+    - Compare 1 vs 1
+  - Compare [2,3,4] vs 4
+    - Mixed types; convert right to [4] and retry comparison
+    - Compare [2,3,4] vs [4]
+      - Compare 2 vs 4
+        - Left side is smaller, so inputs are in the right order
+
+== Pair 3 ==
+- Compare [9] vs [[8,7,6]]
+  - Compare 9 vs [8,7,6]
+    - Mixed types; convert left to [9] and retry comparison
+    - Compare [9] vs [8,7,6]
+      - Compare 9 vs 8
+        - Right side is smaller, so inputs are not in the right order
+
+== Pair 4 ==
+- Compare [[4,4],4,4] vs [[4,4],4,4,4]
+  - Compare [4,4] vs [4,4]
+    - Compare 4 vs 4
+    - Compare 4 vs 4
+  - Compare 4 vs 4
+  - Compare 4 vs 4
+  - Left side ran out of items, so inputs are in the right order
+"
+        ));
+        assert!(explanation.ends_with(
+            "- Compare [1,[2,[3,[4,[5,6,7]]]],8,9] vs [1,[2,[3,[4,[5,6,0]]]],8,9]
+  - Compare 1 vs 1
+  - Compare [2,[3,[4,[5,6,7]]]] vs [2,[3,[4,[5,6,0]]]]
+    - Compare 2 vs 2
+    - Compare [3,[4,[5,6,7]]] vs [3,[4,[5,6,0]]]
+      - Compare 3 vs 3
+      - Compare [4,[5,6,7]] vs [4,[5,6,0]]
+        - Compare 4 vs 4
+        - Compare [5,6,7] vs [5,6,0]
+          - Compare 5 vs 5
+          - Compare 6 vs 6
+          - Compare 7 vs 0
+            - Right side is smaller, so inputs are not in the right order"
+        ));
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value = serde_json::from_str("[1,[2,[3]],[]]").unwrap();
+        let packet = Packet::try_from(json.clone()).unwrap();
+        assert_eq!(packet.to_string(), "[1,[2,[3]],[]]");
+        assert_eq!(serde_json::Value::from(&packet), json);
+        assert_eq!(serde_json::to_string(&packet).unwrap(), packet.to_string());
+        assert!(Packet::try_from(serde_json::json!([1, "two"])).is_err());
+        assert!(Packet::try_from(serde_json::json!([-1])).is_err());
     }
 
     #[test]
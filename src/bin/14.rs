@@ -1,11 +1,15 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{char, digit1, newline},
-    multi::separated_list0,
+    character::complete::char,
+    combinator::map,
+    multi::separated_list1,
     sequence::separated_pair,
     IResult,
 };
-use std::ops::RangeInclusive;
+use advent_of_code::helpers::grid::Grid;
+use advent_of_code::helpers::parse::{lines, parse_all, unsigned};
+use advent_of_code::helpers::point::Point2;
+use advent_of_code::recorder::{Frame, Recorder};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Material {
@@ -14,253 +18,198 @@ enum Material {
     Rock,
 }
 
-#[derive(Debug, Clone)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
+type Point = Point2<isize>;
 
-impl Coord {
-    fn down(&self) -> Self {
-        Coord {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
+const DOWN: Point = Point::new(0, 1);
+const DOWN_LEFT: Point = Point::new(-1, 1);
+const DOWN_RIGHT: Point = Point::new(1, 1);
 
-    fn down_left(&self) -> Self {
-        Coord {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
+struct Cave {
+    start: Point,
+    grid: Grid<Material>,
+}
 
-    fn down_right(&self) -> Self {
-        Coord {
-            x: self.x + 1,
-            y: self.y + 1,
+impl Material {
+    fn to_char(&self) -> char {
+        match self {
+            Material::Air => '.',
+            Material::Sand => 'o',
+            Material::Rock => '#',
         }
     }
 }
 
-struct Grid {
-    start: Coord,
-    height: usize,
-    width: usize,
-    height_offset: usize,
-    width_offset: usize,
-    grid: Vec<Vec<Material>>,
+fn grid_to_frame(grid: &Grid<Material>) -> Frame {
+    Frame::new(
+        grid.rows()
+            .map(|row| row.iter().map(Material::to_char).collect())
+            .collect(),
+    )
 }
 
-fn print_grid(grid: &Vec<Vec<Material>>) {
-    for row in grid.iter() {
-        for col in row.iter() {
-            match col {
-                Material::Air => print!("."),
-                Material::Sand => print!("o"),
-                Material::Rock => print!("#"),
-            }
-        }
-        println!();
-    }
-    println!();
-}
+impl Cave {
+    fn new(parsed_input: Vec<Vec<Point>>, is_part_one: bool) -> Self {
+        let start = Point::new(500, 0);
+        let height = parsed_input.iter().flatten().map(|p| p.y).max().unwrap() + 3;
 
-fn get_range(a: usize, b: usize) -> RangeInclusive<usize> {
-    if a < b {
-        a..=b
-    } else {
-        b..=a
-    }
-}
+        // sand piles up in a triangle below the start, make room for it
+        let min_x = parsed_input.iter().flatten().map(|p| p.x).min().unwrap() - 1;
+        let max_x = parsed_input.iter().flatten().map(|p| p.x).max().unwrap() + 1;
+        let min_x = min_x.min(start.x - height);
+        let max_x = max_x.max(start.x + height);
+        let width = max_x - min_x + 1;
 
-impl Grid {
-    fn new(input: Vec<Vec<(&str, &str)>>, is_part_one: bool) -> Self {
-        let start = Coord { x: 500, y: 0 };
-        let parsed_input: Vec<Vec<(usize, usize)>> = input
-            .iter()
-            .map(|line| {
-                line.iter()
-                    .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-                    .collect()
-            })
-            .collect();
-        let min_height = 0;
-        let max_height = parsed_input
-            .iter()
-            .flatten()
-            .map(|(_, y)| y)
-            .max()
-            .unwrap()
-            .clone()
-            + 2;
-        let height = max_height - min_height + 1;
-
-        let mut min_width = parsed_input
-            .iter()
-            .flatten()
-            .map(|(x, _)| x)
-            .min()
-            .unwrap()
-            .clone()
-            - 1;
-        let mut max_width = parsed_input
-            .iter()
-            .flatten()
-            .map(|(x, _)| x)
-            .max()
-            .unwrap()
-            .clone()
-            + 1;
-        min_width = if min_width < 500 - height { min_width } else { 500 - height };
-        max_width = if max_width > 500 + height { max_width } else { 500 + height };
-        let width = max_width - min_width + 1;
-
-        let mut grid = vec![vec![Material::Air; width]; height];
+        let mut grid =
+            Grid::new(width as usize, height as usize, Material::Air).with_origin((min_x, 0));
 
         for line in parsed_input {
             for w in line.windows(2) {
-                let (x1, y1) = w[0];
-                let (x2, y2) = w[1];
-                if x1 != x2 {
-                    for x in get_range(x1, x2) {
-                        grid[y1 - min_height][x - min_width] = Material::Rock;
-                    }
-                } else {
-                    for y in get_range(y1, y2) {
-                        grid[y - min_height][x1 - min_width] = Material::Rock;
-                    }
+                let direction = (w[1] - w[0]).signum();
+                let mut current = w[0];
+                grid[current] = Material::Rock;
+                while current != w[1] {
+                    current += direction;
+                    grid[current] = Material::Rock;
                 }
             }
         }
 
         if !is_part_one {
-            for x in 0..width {
-                grid[height - 1][x] = Material::Rock;
+            for x in min_x..=max_x {
+                grid[(x, height - 1)] = Material::Rock;
             }
         }
 
-        Grid {
-            start: Coord {
-                x: start.x - min_width,
-                y: start.y - min_height,
-            },
-            height,
-            width,
-            height_offset: min_height,
-            width_offset: min_width,
-            grid,
-        }
+        Cave { start, grid }
     }
 
-    fn is_empty(&self, coord: &Coord) -> bool {
-        match self.grid[coord.y][coord.x] {
-            Material::Air => true,
-            _ => false,
-        }
+    fn is_empty(&self, pos: Point) -> bool {
+        self.grid[pos] == Material::Air
     }
 
-    fn is_freefall(&self, coord: &Coord) -> bool {
-        coord.y >= self.height - 1
+    fn is_freefall(&self, pos: Point) -> bool {
+        pos.y >= self.grid.height() as isize - 1
     }
 
-    fn generate_sand(&mut self) -> u32 {
+    fn generate_sand(&mut self, recorder: &mut Recorder) -> u32 {
         let mut sand_counter = 0;
         loop {
-            if self.grid[self.start.y][self.start.x] == Material::Sand {
+            if self.grid[self.start] == Material::Sand {
                 return sand_counter;
             }
 
-            let mut current = self.start.clone();
+            let mut current = self.start;
             loop {
-                if self.is_freefall(&current) {
+                if self.is_freefall(current) {
                     return sand_counter;
-                } else if self.is_empty(&current.down()) {
-                    current = current.down();
-                } else if self.is_empty(&current.down_left()) {
-                    current = current.down_left();
-                } else if self.is_empty(&current.down_right()) {
-                    current = current.down_right();
+                } else if self.is_empty(current + DOWN) {
+                    current += DOWN;
+                } else if self.is_empty(current + DOWN_LEFT) {
+                    current += DOWN_LEFT;
+                } else if self.is_empty(current + DOWN_RIGHT) {
+                    current += DOWN_RIGHT;
                 } else {
-                    self.grid[current.y][current.x] = Material::Sand;
+                    self.grid[current] = Material::Sand;
                     break;
                 }
             }
             sand_counter += 1;
-            // print_grid(&self.grid);
+            recorder.record(|| grid_to_frame(&self.grid));
         }
     }
 
-    fn print(&self) {
-        println!(
-            "Width: {} offset {}, height: {} offset {}",
-            self.width, self.width_offset, self.height, self.height_offset
+    fn log(&self) {
+        advent_of_code::debug!(
+            "Width: {} offset {}, height: {}",
+            self.grid.width(),
+            self.grid.origin().0,
+            self.grid.height()
         );
-
-        print_grid(&self.grid);
+        advent_of_code::trace!("\n{}", grid_to_frame(&self.grid));
     }
 }
 
 // Example input:
 // 498,4 -> 498,6 -> 496,6
 // 503,4 -> 502,4 -> 502,9 -> 494,9
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<(&str, &str)>>> {
-    separated_list0(newline, parse_line)(input)
+fn parse_input(input: &str) -> Vec<Vec<Point>> {
+    parse_all(lines(parse_line), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
-fn parse_line(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
-    separated_list0(tag(" -> "), parse_pair)(input)
+fn parse_line(input: &str) -> IResult<&str, Vec<Point>> {
+    separated_list1(tag(" -> "), parse_pair)(input)
 }
 
-fn parse_pair(input: &str) -> IResult<&str, (&str, &str)> {
-    separated_pair(digit1, char(','), digit1)(input)
+fn parse_pair(input: &str) -> IResult<&str, Point> {
+    map(separated_pair(unsigned, char(','), unsigned), |(x, y)| {
+        Point::new(x, y)
+    })(input)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (_, parsed_input) = parse_input(input).unwrap();
-    let mut grid = Grid::new(parsed_input, true);
-    grid.print();
-    Some(grid.generate_sand())
+    let parsed_input = parse_input(input);
+    let mut cave = Cave::new(parsed_input, true);
+    cave.log();
+    Some(cave.generate_sand(&mut Recorder::disabled()))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, parsed_input) = parse_input(input).unwrap();
-    let mut grid = Grid::new(parsed_input, false);
-    grid.print();
-    Some(grid.generate_sand())
+    let parsed_input = parse_input(input);
+    let mut cave = Cave::new(parsed_input, false);
+    cave.log();
+    Some(cave.generate_sand(&mut Recorder::disabled()))
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_14;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(14, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 14);
+    advent_of_code::fingerprint::check(14, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::recorder::record_from_args(|recorder| {
+        let parsed_input = parse_input(input);
+        Cave::new(parsed_input, false).generate_sand(recorder);
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 14);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(14, part_one, part_two);
+    }
+
     #[test]
     fn test_parse_pair() {
-        assert_eq!(parse_pair("1,2"), Ok(("", ("1", "2"))));
+        assert_eq!(parse_pair("1,2"), Ok(("", Point::new(1, 2))));
     }
 
     #[test]
     fn test_parse_line() {
         assert_eq!(
             parse_line("1,2 -> 3,4"),
-            Ok(("", vec![("1", "2"), ("3", "4")]))
+            Ok(("", vec![Point::new(1, 2), Point::new(3, 4)]))
         );
     }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_input("1,2 -> 3,4\n5,6 -> 7,8"),
-            Ok((
-                "",
-                vec![vec![("1", "2"), ("3", "4")], vec![("5", "6"), ("7", "8")]]
-            ))
+            parse_input("1,2 -> 3,4\n5,6 -> 7,8\n"),
+            vec![
+                vec![Point::new(1, 2), Point::new(3, 4)],
+                vec![Point::new(5, 6), Point::new(7, 8)]
+            ]
         );
     }
 
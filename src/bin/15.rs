@@ -1,87 +1,78 @@
-use nested_intervals::IntervalSet;
-use nom::{
-    bytes::complete::{tag, take_while1},
-    character::complete::newline,
-    multi::many1,
-    IResult,
+use advent_of_code::helpers::{
+    interval::{Interval, IntervalSet},
+    parse::{lines, parse_all, signed},
+    point::Point2,
 };
-use num::Integer;
-use std::ops::Range;
-use std::{collections::HashSet, ops::RangeInclusive};
-
-#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
+use advent_of_code::params::Param;
+use nom::{bytes::complete::tag, IResult};
+use std::collections::{BTreeSet, HashSet};
 
-impl Coord {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
+type Coord = Point2<i64>;
 
-    fn distance_to(&self, other: &Self) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
-    }
+/// Row checked in part one.
+const TARGET_Y: Param<i64> = Param::new("target_y", 2_000_000);
+/// Part two searches the square from `(search_min, search_min)` to `(search_max, search_max)`.
+const SEARCH_MIN: Param<i64> = Param::new("search_min", 0);
+const SEARCH_MAX: Param<i64> = Param::new("search_max", 4_000_000);
+const FREQUENCY_MULTIPLIER: Param<i64> = Param::new("frequency_multiplier", 4_000_000);
+
+fn tuning_frequency(coord: &Coord) -> i64 {
+    coord.x * FREQUENCY_MULTIPLIER.get() + coord.y
+}
+
+/// Diagonal lines, `x + y = c` ("sums") and `x - y = c` ("differences").
+#[derive(Debug, Default, PartialEq)]
+struct Diagonals {
+    sums: Vec<i64>,
+    differences: Vec<i64>,
+}
 
-    fn to_answer(&self) -> u64 {
-        (self.x as u64) * 4000000 + (self.y as u64)
+impl Diagonals {
+    /// Lattice points where a sum line crosses a difference line.
+    fn intersections(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.sums.iter().flat_map(move |sum| {
+            self.differences
+                .iter()
+                .filter(move |difference| (sum - *difference) % 2 == 0)
+                .map(move |difference| Coord::new((sum + difference) / 2, (sum - difference) / 2))
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-struct DiagonalRange {
-    start: Coord,
-    end: Coord,
-    a: i32, // 1 or -1 depending on whether the diagonal is increasing or decreasing
-    b: i32, // offset from the (0, 0) coordinate
-    x_range: RangeInclusive<i32>,
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bounds {
+    min: Coord,
+    max: Coord,
 }
 
-impl DiagonalRange {
-    fn new(start: Coord, end: Coord) -> Self {
-        if (start.x - end.x).abs() != (start.y - end.y).abs() {
-            panic!("DiagonalRange must be diagonal");
-        }
-        // Flip the coordinates if start is to the right of end
-        let (start, end) = if start.x <= end.x {
-            (start, end)
-        } else {
-            (end, start)
-        };
-        let a = if start.y <= end.y { 1 } else { -1 };
-        let b = start.y - a * start.x;
-        let x_range = start.x..=end.x;
-        Self {
-            start,
-            end,
-            a,
-            b,
-            x_range,
-        }
+impl Bounds {
+    fn contains(&self, coord: &Coord) -> bool {
+        (self.min.x..=self.max.x).contains(&coord.x) && (self.min.y..=self.max.y).contains(&coord.y)
     }
 
-    fn intersect(&self, other: &Self) -> Option<Coord> {
-        if self.a == other.a {
-            // Parallel
-            None
-        } else if self.b.is_even() != other.b.is_even() {
-            // Do not intersect in a point
-            None
-        } else {
-            let x = (other.b - self.b) / (self.a - other.a);
-            let y = self.a * x + self.b;
-            let coord = Coord::new(x, y);
-            if self.contains(&coord) && other.contains(&coord) {
-                Some(coord)
-            } else {
-                None
-            }
-        }
+    fn corners(&self) -> [Coord; 4] {
+        [
+            self.min,
+            Coord::new(self.max.x, self.min.y),
+            Coord::new(self.min.x, self.max.y),
+            self.max,
+        ]
     }
 
-    fn contains(&self, coord: &Coord) -> bool {
-        self.x_range.contains(&coord.x) && self.a * coord.x + self.b == coord.y
+    /// Points where the diagonals cross the edges of the box, they may lie outside of it.
+    fn edge_crossings<'a>(&'a self, diagonals: &'a Diagonals) -> impl Iterator<Item = Coord> + 'a {
+        let (xs, ys) = ([self.min.x, self.max.x], [self.min.y, self.max.y]);
+        let sums = diagonals.sums.iter().flat_map(move |sum| {
+            xs.into_iter()
+                .map(move |x| Coord::new(x, sum - x))
+                .chain(ys.into_iter().map(move |y| Coord::new(sum - y, y)))
+        });
+        let differences = diagonals.differences.iter().flat_map(move |difference| {
+            xs.into_iter()
+                .map(move |x| Coord::new(x, x - difference))
+                .chain(ys.into_iter().map(move |y| Coord::new(difference + y, y)))
+        });
+        sums.chain(differences)
     }
 }
 
@@ -89,11 +80,11 @@ impl DiagonalRange {
 struct Sensor {
     sensor: Coord,
     beacon: Coord,
-    range: i32,
+    range: i64,
 }
 
 impl Sensor {
-    fn new(sx: i32, sy: i32, bx: i32, by: i32) -> Self {
+    fn new(sx: i64, sy: i64, bx: i64, by: i64) -> Self {
         let sensor = Coord::new(sx, sy);
         let beacon = Coord::new(bx, by);
         if sensor == beacon {
@@ -102,129 +93,62 @@ impl Sensor {
         Self {
             sensor,
             beacon,
-            range: sensor.distance_to(&beacon),
+            range: sensor.manhattan(&beacon),
         }
     }
 
-    fn distance_to(&self, other: &Self) -> i32 {
-        self.sensor.distance_to(&other.sensor)
+    // Sensor with the closest beacon straight to the right, `range` steps away.
+    #[cfg(test)]
+    fn with_range(sx: i64, sy: i64, range: i64) -> Self {
+        Self::new(sx, sy, sx + range, sy)
     }
 
     fn outside_range(&self, coord: &Coord) -> bool {
-        self.sensor.distance_to(coord) > self.range
+        self.sensor.manhattan(coord) > self.range
     }
 
-    fn overlap_on_y_coord(&self, target_y: i32) -> Option<Range<i32>> {
-        let range = self.sensor.distance_to(&self.beacon);
+    fn overlap_on_y_coord(&self, target_y: i64) -> Option<Interval<i64>> {
         let distance_to_y = (self.sensor.y - target_y).abs();
-        if distance_to_y <= range {
-            let x1 = self.sensor.x - (range - distance_to_y);
-            let x2 = self.sensor.x + (range - distance_to_y);
-            Some(x1..x2 + 1)
+        if distance_to_y <= self.range {
+            let x1 = self.sensor.x - (self.range - distance_to_y);
+            let x2 = self.sensor.x + (self.range - distance_to_y);
+            Some(Interval::new(x1, x2))
         } else {
             None
         }
     }
 
-    fn overlaps_perimeter(&self, other: &Self) -> bool {
-        let completely_out_or_touching = self.distance_to(other) > self.range + other.range;
-        let completely_in = self.distance_to(other) < (self.range - other.range).abs();
-        !completely_out_or_touching && !completely_in
-    }
-
-    fn x_perimeter_right(&self) -> Coord {
-        Coord::new(self.sensor.x + self.range + 1, self.sensor.y)
-    }
-
-    fn x_perimeter_left(&self) -> Coord {
-        Coord::new(self.sensor.x - self.range - 1, self.sensor.y)
-    }
-
-    fn y_perimeter_bottom(&self) -> Coord {
-        Coord::new(self.sensor.x, self.sensor.y - self.range - 1)
-    }
-
-    fn y_perimeter_top(&self) -> Coord {
-        Coord::new(self.sensor.x, self.sensor.y + self.range + 1)
-    }
-
-    fn perimeter_diagonals(&self) -> Vec<DiagonalRange> {
-        let left_top_diagonal = DiagonalRange::new(self.x_perimeter_left(), self.y_perimeter_top());
-        let bottom_right_diagonal =
-            DiagonalRange::new(self.y_perimeter_bottom(), self.x_perimeter_right());
-        let left_bottom_diagonal =
-            DiagonalRange::new(self.x_perimeter_left(), self.y_perimeter_bottom());
-        let top_right_diagonal =
-            DiagonalRange::new(self.y_perimeter_top(), self.x_perimeter_right());
-        vec![
-            left_top_diagonal,
-            bottom_right_diagonal,
-            left_bottom_diagonal,
-            top_right_diagonal,
-        ]
-    }
-
-    fn perimeter_intersection(&self, other: &Self) -> HashSet<Coord> {
-        let self_diagonals = self.perimeter_diagonals();
-        let other_diagonals = other.perimeter_diagonals();
-        let mut intersections = HashSet::new();
-        for self_diagonal in self_diagonals {
-            for other_diagonal in &other_diagonals {
-                if let Some(intersection) = self_diagonal.intersect(other_diagonal) {
-                    intersections.insert(intersection);
-                }
-            }
+    /// The four lines through the points just outside of the sensor's range.
+    fn perimeter(&self) -> Diagonals {
+        let (sum, difference) = (self.sensor.x + self.sensor.y, self.sensor.x - self.sensor.y);
+        let distance = self.range + 1;
+        Diagonals {
+            sums: vec![sum - distance, sum + distance],
+            differences: vec![difference - distance, difference + distance],
         }
-        intersections
     }
 }
 
-fn is_digit_minus(c: char) -> bool {
-    c.is_digit(10) || c == '-'
-}
-
 // Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 fn parse_line(input: &str) -> IResult<&str, Sensor> {
     let (input, _) = tag("Sensor at x=")(input)?;
-    let (input, x) = take_while1(is_digit_minus)(input)?;
+    let (input, x) = signed(input)?;
     let (input, _) = tag(", y=")(input)?;
-    let (input, y) = take_while1(is_digit_minus)(input)?;
+    let (input, y) = signed(input)?;
     let (input, _) = tag(": closest beacon is at x=")(input)?;
-    let (input, bx) = take_while1(is_digit_minus)(input)?;
+    let (input, bx) = signed(input)?;
     let (input, _) = tag(", y=")(input)?;
-    let (input, by) = take_while1(is_digit_minus)(input)?;
-    let (input, _) = newline(input)?;
-    Ok((
-        input,
-        Sensor::new(
-            x.parse().unwrap(),
-            y.parse().unwrap(),
-            bx.parse().unwrap(),
-            by.parse().unwrap(),
-        ),
-    ))
+    let (input, by) = signed(input)?;
+    Ok((input, Sensor::new(x, y, bx, by)))
 }
 
 fn parse_input(input: &str) -> Vec<Sensor> {
-    many1(parse_line)(input).unwrap().1
+    parse_all(lines(parse_line), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
-pub fn part_one(input: &str, target_y: i32) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u32> {
     let sensors = parse_input(input);
-    let mut min_x = sensors
-        .iter()
-        .map(|s| [s.sensor.x, s.beacon.x])
-        .flatten()
-        .min()
-        .unwrap();
-    let max_x = sensors
-        .iter()
-        .map(|s| [s.sensor.x, s.beacon.x])
-        .flatten()
-        .max()
-        .unwrap();
-    let width_x = max_x - min_x;
-    min_x -= width_x;
+    let target_y = TARGET_Y.get();
 
     let beacons_on_target_y: HashSet<Coord> = sensors
         .iter()
@@ -232,91 +156,109 @@ pub fn part_one(input: &str, target_y: i32) -> Option<u32> {
         .map(|s| s.beacon)
         .collect();
 
-    let target_line_overlaps = sensors
+    let covered: IntervalSet<i64> = sensors
         .iter()
         .filter_map(|s| s.overlap_on_y_coord(target_y))
-        .collect::<Vec<_>>();
+        .collect();
 
-    let offset_overlaps = target_line_overlaps
-        .iter()
-        .map(|r| (r.start - min_x) as u32..(r.end - min_x) as u32)
-        .collect::<Vec<_>>();
+    Some(covered.len() as u32 - beacons_on_target_y.len() as u32)
+}
 
-    let mut interval_set = IntervalSet::new(&offset_overlaps).ok().unwrap();
-    let overlap_count = interval_set.covered_units();
+/// Every point in `bounds` that no sensor covers, assuming there are only a few isolated ones.
+/// Such a point is surrounded by covered points or the edges of the box, so it lies where the
+/// perimeters of two sensors cross, where a perimeter crosses an edge, or on a corner of the box.
+fn uncovered_points(sensors: &[Sensor], bounds: Bounds) -> Vec<Coord> {
+    let mut diagonals = Diagonals::default();
+    for perimeter in sensors.iter().map(Sensor::perimeter) {
+        diagonals.sums.extend(perimeter.sums);
+        diagonals.differences.extend(perimeter.differences);
+    }
 
-    Some(overlap_count - beacons_on_target_y.len() as u32)
-}
+    let candidates: BTreeSet<Coord> = diagonals
+        .intersections()
+        .chain(bounds.edge_crossings(&diagonals))
+        .chain(bounds.corners())
+        .filter(|coord| bounds.contains(coord))
+        .collect();
+    advent_of_code::debug!("checking {} candidates", candidates.len());
 
-pub fn part_one_run(input: &str) -> Option<u32> {
-    part_one(input, 2000000)
+    candidates
+        .into_iter()
+        .filter(|coord| sensors.iter().all(|s| s.outside_range(coord)))
+        .collect()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+/// Every uncovered point in the search area.
+fn search(input: &str) -> Vec<Coord> {
     let sensors = parse_input(input);
-    let mut checked_coords: HashSet<Coord> = HashSet::new();
-
-    for i in 0..sensors.len() {
-        let i_sensor = &sensors[i].clone();
-        println!("Checking sensor {}, {:?}", i, i_sensor);
-
-        for j in (i + 1)..sensors.len() {
-            let j_sensor = &sensors[j].clone();
-            if !i_sensor.overlaps_perimeter(j_sensor) {
-                continue;
-            }
-
-            for k in (j + 1)..sensors.len() {
-                let condition = i == 3 && j == 6 && k == 9;
-                if condition {
-                    println!("Checking sensor {}, {:?}", k, sensors[k]);
-                }
-                let k_sensor = &sensors[k].clone();
-                if !i_sensor.overlaps_perimeter(k_sensor) {
-                    continue;
-                }
-                // J and K must be only touching
-                if j_sensor.distance_to(&k_sensor) != j_sensor.range + k_sensor.range + 2 {
-                    continue;
-                }
-                let ij_intersection = i_sensor.perimeter_intersection(j_sensor);
-                let ik_intersection = i_sensor.perimeter_intersection(k_sensor);
-                let target_coord_set = &ij_intersection & &ik_intersection;
-                if target_coord_set.len() == 1 {
-                    let target_coord = target_coord_set.iter().next().unwrap();
-                    if checked_coords.contains(target_coord) {
-                        continue;
-                    }
-
-                    if sensors
-                        .iter()
-                        .all(|s| s.outside_range(target_coord))
-                    {
-                        println!("Found target: {:?}", target_coord);
-                        return Some(target_coord.to_answer());
-                    } else {
-                        checked_coords.insert(target_coord.clone());
-                    }
-                }
-            }
+    let (min, max) = (SEARCH_MIN.get(), SEARCH_MAX.get());
+    let bounds = Bounds {
+        min: Coord::new(min, min),
+        max: Coord::new(max, max),
+    };
+    uncovered_points(&sensors, bounds)
+}
+
+pub fn part_two(input: &str) -> Option<i64> {
+    match search(input).as_slice() {
+        [point] => Some(tuning_frequency(point)),
+        points => {
+            advent_of_code::info!(
+                "expected exactly one uncovered point, found {}, run with --explain to list them",
+                points.len()
+            );
+            None
         }
     }
-    None
 }
 
+fn explain(input: &str) -> String {
+    let points = search(input);
+    if points.is_empty() {
+        return "no uncovered point".to_string();
+    }
+    points
+        .iter()
+        .map(|point| {
+            format!(
+                "uncovered point {}, tuning frequency {}",
+                point,
+                tuning_frequency(point)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_15;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(15, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 15);
-    advent_of_code::solve!(1, part_one_run, input);
+    advent_of_code::fingerprint::check(15, input, FINGERPRINT);
+    advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::explain::explain_from_args(|| explain(input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 15);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(15, part_one, part_two);
+    }
+
     #[test]
     fn test_parse_line() {
-        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n";
+        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
         let (input, sensor) = parse_line(input).unwrap();
         assert_eq!(input, "");
         assert_eq!(sensor, Sensor::new(2, 18, -2, 15));
@@ -326,46 +268,88 @@ mod tests {
     fn test_distance() {
         let a = Coord::new(0, 11);
         let b = Coord::new(2, 10);
-        assert_eq!(a.distance_to(&b), 3);
+        assert_eq!(a.manhattan(&b), 3);
+    }
+
+    #[test]
+    fn test_perimeter() {
+        let sensor = Sensor::with_range(12, 14, 4);
+        let perimeter = sensor.perimeter();
+        assert_eq!(perimeter.sums, vec![21, 31]);
+        assert_eq!(perimeter.differences, vec![-7, 3]);
+
+        let mut corners: Vec<Coord> = perimeter.intersections().collect();
+        corners.sort();
+        assert_eq!(
+            corners,
+            vec![
+                Coord::new(7, 14),
+                Coord::new(12, 9),
+                Coord::new(12, 19),
+                Coord::new(17, 14)
+            ]
+        );
     }
 
     #[test]
-    fn test_diagonals() {
-        let sensor = Sensor::new2(12, 14, 4);
-        let diagonals = sensor.perimeter_diagonals();
-        let target_diagonals = vec![
-            DiagonalRange::new(Coord::new(7, 14), Coord::new(12, 19)),
-            DiagonalRange::new(Coord::new(17, 14), Coord::new(12, 9)),
-            DiagonalRange::new(Coord::new(7, 14), Coord::new(12, 9)),
-            DiagonalRange::new(Coord::new(17, 14), Coord::new(12, 19)),
+    fn test_uncovered_points() {
+        let bounds = Bounds {
+            min: Coord::new(0, 0),
+            max: Coord::new(4, 4),
+        };
+        // covers everything but the corners (0, 0) and (4, 4)
+        let sensors = vec![
+            Sensor::with_range(2, 2, 3),
+            Sensor::with_range(4, 0, 3),
+            Sensor::with_range(0, 4, 3),
         ];
-        assert_eq!(diagonals, target_diagonals);
+        assert_eq!(
+            uncovered_points(&sensors, bounds),
+            vec![Coord::new(0, 0), Coord::new(4, 4)]
+        );
+        // a single point between four sensors
+        let sensors = vec![
+            Sensor::with_range(0, 0, 3),
+            Sensor::with_range(4, 0, 3),
+            Sensor::with_range(0, 4, 3),
+            Sensor::with_range(4, 4, 3),
+        ];
+        let bounds = Bounds {
+            min: Coord::new(1, 1),
+            max: Coord::new(3, 3),
+        };
+        assert_eq!(uncovered_points(&sensors, bounds), vec![Coord::new(2, 2)]);
     }
 
     #[test]
-    fn test_intersect() {
-        let range1 = DiagonalRange::new(Coord::new(0, 0), Coord::new(4, 4));
-        let range2 = DiagonalRange::new(Coord::new(2, 2), Coord::new(6, 6));
-        let range3 = DiagonalRange::new(Coord::new(0, 0), Coord::new(4, -4));
-        let range4 = DiagonalRange::new(Coord::new(-2, 2), Coord::new(2, -2));
-        let range5 = DiagonalRange::new(Coord::new(-1, 2), Coord::new(2, -1));
-
-        assert_eq!(range1.intersect(&range2), None);
-        assert_eq!(range1.intersect(&range3), Some(Coord::new(0, 0)));
-        assert_eq!(range1.intersect(&range4), Some(Coord::new(0, 0)));
-        assert_eq!(range1.intersect(&range5), None);
-        assert_eq!(range4.intersect(&range5), None);
+    fn test_several_uncovered_points() {
+        let input = "Sensor at x=2, y=2: closest beacon is at x=5, y=2
+Sensor at x=4, y=0: closest beacon is at x=7, y=0
+Sensor at x=0, y=4: closest beacon is at x=0, y=7
+";
+        advent_of_code::params::with_overrides([("search_max", 4)], || {
+            assert_eq!(part_two(input), None);
+            assert_eq!(
+                explain(input),
+                "uncovered point (0, 0), tuning frequency 0\n\
+                 uncovered point (4, 4), tuning frequency 16000004"
+            );
+        });
     }
 
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 15);
-        assert_eq!(part_one(&input, 10), Some(26));
+        let answer =
+            advent_of_code::params::with_overrides([("target_y", 10)], || part_one(&input));
+        assert_eq!(answer, Some(26));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 15);
-        assert_eq!(part_two(&input), Some(56000011));
+        let answer =
+            advent_of_code::params::with_overrides([("search_max", 20)], || part_two(&input));
+        assert_eq!(answer, Some(56000011));
     }
 }
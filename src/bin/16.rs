@@ -1,31 +1,39 @@
-use std::{collections::HashMap, cmp::Reverse};
+use std::{collections::HashMap, cmp::Reverse, fmt};
 
-use fast_paths::InputGraph;
+use advent_of_code::{
+    helpers::{
+        parse::{lines, parse_all, unsigned},
+        search::{all_pairs, bfs},
+    },
+    params::Param,
+};
 use nom::{
-    bytes::complete::{tag, take, take_while1, is_a},
-    character::complete::newline,
-    multi::{many1, separated_list0},
+    bytes::complete::{tag, take},
+    multi::separated_list0,
     IResult, branch::alt,
 };
 
+const MINUTES: Param<u32> = Param::new("minutes", 30);
+/// Minutes it takes to teach the elephant how to open valves in part two.
+const TEACHING_MINUTES: u32 = 4;
+
 fn parse_line(input: &str) -> IResult<&str, Valve> {
     let (input, _) = tag("Valve ")(input)?;
     let (input, name) = take(2usize)(input)?;
     let (input, _) = tag(" has flow rate=")(input)?;
-    let (input, flow_rate) = take_while1(|c: char| c.is_digit(10))(input)?;
+    let (input, flow_rate) = unsigned(input)?;
     let (input, _) = tag("; ")(input)?;
     let (input, _) = alt((tag("tunnels lead"), tag("tunnel leads")))(input)?;
     let (input, _) = tag(" to ")(input)?;
     let (input, _) = alt((tag("valves"), tag("valve")))(input)?;
     let (input, _) = tag(" ")(input)?;
     let (input, tunnels) = separated_list0(tag(", "), take(2usize))(input)?;
-    let (input, _) = newline(input)?;
     let tunnels = tunnels.iter().map(|s| s.to_string()).collect();
     Ok((
         input,
         Valve::new(
             name.to_string(),
-            flow_rate.parse().unwrap(),
+            flow_rate,
             tunnels,
             0,
         ),
@@ -33,10 +41,7 @@ fn parse_line(input: &str) -> IResult<&str, Valve> {
 }
 
 fn parse_input(input: &str) -> Vec<Valve> {
-    let (input, mut valves) = many1(parse_line)(input).unwrap();
-    if input.len() > 0 {
-        panic!("Failed to parse input");
-    }
+    let mut valves = parse_all(lines(parse_line), input).unwrap_or_else(|e| panic!("{}", e));
     // Sort by flow rate descending
     valves.sort_by_key(|v| Reverse(v.flow_rate));
     // Update index
@@ -65,163 +70,380 @@ impl Valve {
     }
 }
 
-fn calculate_fast_graph(valves: &[Valve]) -> Vec<Vec<usize>> {
-    let mut input_graph = InputGraph::new();
+/// Minutes between all valves, `None` if a valve can't be reached from another one.
+fn calculate_fast_graph(valves: &[Valve]) -> Vec<Vec<Option<usize>>> {
     let mut name_to_index = HashMap::new();
 
     for (index, valve) in valves.iter().enumerate() {
         name_to_index.insert(&valve.name, index);
     }
 
-    for (index, valve) in valves.iter().enumerate() {
-        for tunnel in &valve.tunnels {
-            let to_index = name_to_index.get(tunnel).unwrap();
-            input_graph.add_edge(index, *to_index, 1);
+    let indices: Vec<usize> = (0..valves.len()).collect();
+    all_pairs(&indices, |index: &usize| {
+        valves[*index]
+            .tunnels
+            .iter()
+            .map(|tunnel| (name_to_index[tunnel], 1))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// The valves worth opening plus the start valve, with travel times between all of them.
+struct Tunnels {
+    /// Index in the parsed valves of every node.
+    nodes: Vec<usize>,
+    flow_rates: Vec<u32>,
+    distances: Vec<Vec<Option<u32>>>,
+    /// Valves `0..valve_count` have a flow rate, valve `i` is bit `i` of an opened mask.
+    valve_count: usize,
+    start: usize,
+}
+
+impl Tunnels {
+    fn new(valves: &[Valve]) -> Tunnels {
+        let fastest_paths = calculate_fast_graph(valves);
+        let aa_index = valves.iter().position(|valve| valve.name == "AA").unwrap();
+        // valves are sorted by flow rate, so the valves worth opening come first. Valves that
+        // can't be reached from the start are never opened.
+        let mut nodes: Vec<usize> = (0..valves.len())
+            .filter(|&index| valves[index].flow_rate > 0)
+            .filter(|&index| fastest_paths[aa_index][index].is_some())
+            .collect();
+        let valve_count = nodes.len();
+        let start = nodes
+            .iter()
+            .position(|&index| index == aa_index)
+            .unwrap_or_else(|| {
+                nodes.push(aa_index);
+                valve_count
+            });
+
+        Tunnels {
+            flow_rates: nodes.iter().map(|&i| valves[i].flow_rate).collect(),
+            distances: nodes
+                .iter()
+                .map(|&from| {
+                    nodes
+                        .iter()
+                        .map(|&to| fastest_paths[from][to].map(|minutes| minutes as u32))
+                        .collect()
+                })
+                .collect(),
+            nodes,
+            valve_count,
+            start,
+        }
+    }
+
+    /// Valves that are still closed and can be opened before time runs out, with the minutes left
+    /// once they are open.
+    fn next_valves(
+        &self,
+        position: usize,
+        minutes_remaining: u32,
+        opened: usize,
+    ) -> impl Iterator<Item = (usize, u32)> + '_ {
+        (0..self.valve_count)
+            .filter(move |next| opened & (1 << next) == 0)
+            .filter_map(move |next| {
+                // one extra minute to open the valve
+                let minutes_needed = self.distances[position][next]? + 1;
+                (minutes_needed < minutes_remaining)
+                    .then(|| (next, minutes_remaining - minutes_needed))
+            })
+    }
+
+    /// Pressure that could at most still be released: the closed valves are opened highest flow
+    /// first, as if every valve was one minute away from the previous one.
+    fn upper_bound(&self, position: usize, minutes_remaining: u32, opened: usize) -> u32 {
+        let mut minutes_remaining = minutes_remaining;
+        // only the start valve can be closed while we stand on it
+        if position < self.valve_count && opened & (1 << position) == 0 {
+            minutes_remaining += 1;
         }
+        (0..self.valve_count)
+            .filter(|valve| opened & (1 << valve) == 0)
+            .map_while(|valve| {
+                minutes_remaining = minutes_remaining.checked_sub(2).filter(|m| *m > 0)?;
+                Some(self.flow_rates[valve] * minutes_remaining)
+            })
+            .sum()
     }
+}
 
-    input_graph.freeze();
-    let intermediate_graph = fast_paths::prepare(&input_graph);
+/// Nodes in the order their valves are opened, with the minutes left once each one is open.
+type Plan = Vec<(usize, u32)>;
 
-    let mut fastest_paths = vec![vec![0; valves.len()]; valves.len()];
-    for (from_index, from_valve) in valves.iter().enumerate() {
-        for (to_index, to_valve) in valves.iter().enumerate() {
-            if from_index == to_index {
-                continue;
+/// Depth-first search for the most pressure one agent can release. States that were already
+/// reached with at least as much pressure, or that cannot beat the best total even in the best
+/// case, are not explored further.
+struct BranchAndBound<'a> {
+    tunnels: &'a Tunnels,
+    seen: HashMap<(usize, u32, usize), u32>,
+    best: u32,
+    plan: Plan,
+    best_plan: Plan,
+}
+
+impl<'a> BranchAndBound<'a> {
+    fn new(tunnels: &'a Tunnels) -> Self {
+        BranchAndBound {
+            tunnels,
+            seen: HashMap::new(),
+            best: 0,
+            plan: Vec::new(),
+            best_plan: Vec::new(),
+        }
+    }
+
+    /// Best pressure and plan in `minutes`, valves in `opened` are left alone.
+    fn run(mut self, minutes: u32, opened: usize) -> (u32, Plan) {
+        self.visit(self.tunnels.start, minutes, opened, 0);
+        (self.best, self.best_plan)
+    }
+
+    fn visit(&mut self, position: usize, minutes_remaining: u32, opened: usize, pressure: u32) {
+        if pressure > self.best {
+            self.best = pressure;
+            self.best_plan = self.plan.clone();
+        }
+        let bound = self
+            .tunnels
+            .upper_bound(position, minutes_remaining, opened);
+        if pressure + bound <= self.best {
+            return;
+        }
+        match self.seen.get(&(position, minutes_remaining, opened)) {
+            Some(&seen) if seen >= pressure => return,
+            _ => {
+                self.seen
+                    .insert((position, minutes_remaining, opened), pressure);
             }
-            let path = fast_paths::calc_path(
-                &intermediate_graph,
-                name_to_index[&from_valve.name],
-                name_to_index[&to_valve.name],
+        }
+
+        let tunnels = self.tunnels;
+        for (next, minutes_remaining) in tunnels.next_valves(position, minutes_remaining, opened) {
+            self.plan.push((next, minutes_remaining));
+            self.visit(
+                next,
+                minutes_remaining,
+                opened | (1 << next),
+                pressure + tunnels.flow_rates[next] * minutes_remaining,
             );
-            if let Some(path) = path {
-                let weight = path.get_weight();
-                fastest_paths[from_index][to_index] = weight;
-            }
+            self.plan.pop();
         }
     }
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let tunnels = Tunnels::new(&parse_input(input));
+    Some(BranchAndBound::new(&tunnels).run(MINUTES.get(), 0).0)
+}
 
-    fastest_paths
+/// Best pressure released for every set of opened valves.
+struct PressurePerMask<'a> {
+    tunnels: &'a Tunnels,
+    best: Vec<u32>,
 }
 
-fn open_valve(
-    minutes_remaining: u32,
-    current_total_pressure_released: u32,
-    current_valve: Valve,
-    valves: &[Valve],
-    valve_is_opened: &Vec<bool>,
-    fastest_paths: &Vec<Vec<usize>>,
-    level: usize,
-) -> u32 {
-    let mut minutes_remaining = minutes_remaining;
-    let mut valve_is_opened = valve_is_opened.clone();
-    // Check if we can open the valve
-    if !valve_is_opened[current_valve.index] && current_valve.flow_rate == 0 || minutes_remaining == 0 {
-        return current_total_pressure_released;
+impl<'a> PressurePerMask<'a> {
+    fn new(tunnels: &'a Tunnels) -> Self {
+        PressurePerMask {
+            tunnels,
+            best: vec![0; 1 << tunnels.valve_count],
+        }
     }
 
-    // Open valve
-    valve_is_opened[current_valve.index] = true;
-    minutes_remaining = minutes_remaining - 1;
-    let current_total_pressure_released = current_total_pressure_released + current_valve.flow_rate * minutes_remaining;
+    fn visit(&mut self, position: usize, minutes_remaining: u32, opened: usize, pressure: u32) {
+        self.best[opened] = self.best[opened].max(pressure);
+        let tunnels = self.tunnels;
+        for (next, minutes_remaining) in tunnels.next_valves(position, minutes_remaining, opened) {
+            self.visit(
+                next,
+                minutes_remaining,
+                opened | (1 << next),
+                pressure + tunnels.flow_rates[next] * minutes_remaining,
+            );
+        }
+    }
 
-    find_next_valve_to_open(minutes_remaining, current_total_pressure_released, current_valve, valves, &valve_is_opened, fastest_paths, level + 1)
+    /// Makes `best[mask]` the best pressure opening any subset of `mask`.
+    fn include_subsets(&mut self) {
+        for valve in 0..self.tunnels.valve_count {
+            for mask in 0..self.best.len() {
+                if mask & (1 << valve) != 0 {
+                    self.best[mask] = self.best[mask].max(self.best[mask ^ (1 << valve)]);
+                }
+            }
+        }
+    }
+}
+
+/// Valves you open when working together with the elephant, the elephant opens the others.
+fn split_valves(tunnels: &Tunnels, minutes: u32) -> (u32, usize) {
+    let mut search = PressurePerMask::new(tunnels);
+    search.visit(tunnels.start, minutes, 0, 0);
+    search.include_subsets();
+
+    // you and the elephant open disjoint sets of valves
+    let best = &search.best;
+    let all = best.len() - 1;
+    (0..=all)
+        .map(|mine| (best[mine] + best[all ^ mine], mine))
+        .max()
+        .unwrap()
 }
 
-fn find_next_valve_to_open(
-    minutes_remaining: u32,
-    current_total_pressure_released: u32,
-    current_valve: Valve,
-    valves: &[Valve],
-    valve_is_opened: &Vec<bool>,
-    fastest_paths: &Vec<Vec<usize>>,
-    level: usize,
-) -> u32 {
-    // Return if we are at the end
-    if minutes_remaining == 0 {
-        return current_total_pressure_released;
+pub fn part_two(input: &str) -> Option<u32> {
+    let tunnels = Tunnels::new(&parse_input(input));
+    let minutes = MINUTES.get().checked_sub(TEACHING_MINUTES)?;
+    Some(split_valves(&tunnels, minutes).0)
+}
+
+#[derive(Debug, PartialEq)]
+struct Step {
+    valve: String,
+    /// The minute during which the valve is opened, it releases pressure from the next minute on.
+    minute: u32,
+    pressure: u32,
+    /// Valves walked through since the previous step, both ends included.
+    path: Vec<String>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "minute {:>2}: open {}, releasing {:>4} ({})",
+            self.minute,
+            self.valve,
+            self.pressure,
+            self.path.join(" -> ")
+        )
     }
+}
 
-    // TODO branch and bound
-    let possible_pressure_released = valves
-        .iter()
-        .filter(|next_valve| !valve_is_opened[next_valve.index] && next_valve.flow_rate > 0)
-        .map(|next_valve| {
-            let minutes_to_valve = fastest_paths[current_valve.index][next_valve.index] as u32;
-            if current_valve == *next_valve || minutes_to_valve > minutes_remaining{
-                return current_total_pressure_released;
+/// Valves walked through on a shortest path between two valves.
+fn travel_path(valves: &[Valve], from: usize, to: usize) -> Vec<String> {
+    let index_of = |name: &String| valves.iter().position(|valve| valve.name == *name).unwrap();
+    bfs(
+        from,
+        |index| {
+            valves[*index]
+                .tunnels
+                .iter()
+                .map(index_of)
+                .collect::<Vec<_>>()
+        },
+        |index| *index == to,
+    )
+    .map_or(Vec::new(), |path| path.nodes)
+    .into_iter()
+    .map(|index| valves[index].name.clone())
+    .collect()
+}
+
+/// The steps of `plan`, starting at `AA` with `minutes` left.
+fn schedule(valves: &[Valve], tunnels: &Tunnels, plan: &Plan, minutes: u32) -> Vec<Step> {
+    let mut previous = tunnels.start;
+    plan.iter()
+        .map(|&(node, minutes_remaining)| {
+            let path = travel_path(valves, tunnels.nodes[previous], tunnels.nodes[node]);
+            previous = node;
+            Step {
+                valve: valves[tunnels.nodes[node]].name.clone(),
+                minute: minutes - minutes_remaining,
+                pressure: tunnels.flow_rates[node] * minutes_remaining,
+                path,
             }
-            // println!("{}{} -> {} in {} minutes {}", "  ".repeat(level),current_valve.name, next_valve.name, minutes_to_valve, current_total_pressure_released);
-            let pressure_released = open_valve(
-                minutes_remaining - minutes_to_valve,
-                current_total_pressure_released,
-                next_valve.clone(),
-                valves,
-                &valve_is_opened,
-                fastest_paths,
-                level + 1,
-            );
-            pressure_released
         })
-        .max()
-        .unwrap_or(current_total_pressure_released);
-
-    possible_pressure_released
+        .collect()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let minutes_remaining = 30u32;
-    let current_total_pressure_released = 0u32;
+fn render_schedule(title: &str, steps: &[Step]) -> String {
+    let total: u32 = steps.iter().map(|step| step.pressure).sum();
+    let mut lines = vec![format!("{}, {} pressure released:", title, total)];
+    lines.extend(steps.iter().map(|step| format!("  {}", step)));
+    lines.join("\n")
+}
 
+fn explain(input: &str) -> String {
     let valves = parse_input(input);
-    // println!("valve names: {:?}", valves.iter().map(|valve| valve.name.to_string()).collect::<Vec<String>>());
-    let fastest_paths = calculate_fast_graph(&valves);
-    let valve_is_opened = vec![false; valves.len()];
+    let tunnels = Tunnels::new(&valves);
 
-    let aa_valve = valves.iter().find(|valve| valve.name == "AA").unwrap();
+    let minutes = MINUTES.get();
+    let (_, plan) = BranchAndBound::new(&tunnels).run(minutes, 0);
+    let mut sections = vec![render_schedule(
+        "Part 1",
+        &schedule(&valves, &tunnels, &plan, minutes),
+    )];
 
-    Some(find_next_valve_to_open(minutes_remaining, current_total_pressure_released, aa_valve.clone(), &valves, &valve_is_opened, &fastest_paths, 0))
+    let minutes = match minutes.checked_sub(TEACHING_MINUTES) {
+        Some(minutes) => minutes,
+        None => {
+            sections.push(format!(
+                "Part 2: not enough time, teaching the elephant takes {} minutes.",
+                TEACHING_MINUTES
+            ));
+            return sections.join("\n\n");
+        }
+    };
+    let (_, mine) = split_valves(&tunnels, minutes);
+    let all = (1 << tunnels.valve_count) - 1;
+    for (title, opened) in [("Part 2, you", all ^ mine), ("Part 2, elephant", mine)] {
+        let (_, plan) = BranchAndBound::new(&tunnels).run(minutes, opened);
+        sections.push(render_schedule(
+            title,
+            &schedule(&valves, &tunnels, &plan, minutes),
+        ));
+    }
+    sections.join("\n\n")
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
-}
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = advent_of_code::fingerprint::DAY_16;
 
 fn main() {
+    advent_of_code::examples::run_if_requested(16, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", 16);
+    advent_of_code::fingerprint::check(16, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::explain::explain_from_args(|| explain(input));
 }
 
-// DD minute 3: 22 * 28 = 616
-// BB minute 6: 13 * 25 = 325
-// JJ minute 11: 11 * 20 = 220
-// HH minute 18: 22 * 12 = 264
-// EE minute 21: 17 * 9 = 153
-// CC minute 24: 19 * 6 = 114
-// Total pressure released: 616 + 325 + 220 + 264 + 153 + 114 = 1622
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint() {
+        let input = advent_of_code::read_file("examples", 16);
+        assert!(FINGERPRINT(&input));
+    }
+
+    #[test]
+    fn test_examples() {
+        advent_of_code::examples::assert_all(16, part_one, part_two);
+    }
+
     #[test]
     fn test_parse_input() {
         let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB\nValve BB has flow rate=13; tunnels lead to valves CC, AA\n";
         let parsed = parse_input(&input);
+        // valves are sorted by flow rate, highest first
         assert_eq!(
             parsed,
             vec![
-                Valve::new(
-                    "AA".to_string(),
-                    0,
-                    vec!["DD".to_string(), "II".to_string(), "BB".to_string()],
-                    0,
-                ),
                 Valve::new(
                     "BB".to_string(),
                     13,
                     vec!["CC".to_string(), "AA".to_string()],
+                    0,
+                ),
+                Valve::new(
+                    "AA".to_string(),
+                    0,
+                    vec!["DD".to_string(), "II".to_string(), "BB".to_string()],
                     1,
                 ),
             ]
@@ -248,9 +470,65 @@ mod tests {
         assert_eq!(part_one(&input), Some(1651));
     }
 
+    #[test]
+    fn test_unreachable_valves() {
+        let input = "Valve AA has flow rate=0; tunnel leads to valve BB
+Valve BB has flow rate=10; tunnel leads to valve AA
+Valve CC has flow rate=100; tunnel leads to valve DD
+Valve DD has flow rate=0; tunnel leads to valve CC
+";
+        let tunnels = Tunnels::new(&parse_input(input));
+        assert_eq!(tunnels.valve_count, 1);
+        assert_eq!(part_one(input), Some(28 * 10));
+    }
+
+    #[test]
+    fn test_too_few_minutes_to_teach() {
+        let input = advent_of_code::read_file("examples", 16);
+        advent_of_code::params::with_overrides([("minutes", 3)], || {
+            assert_eq!(part_two(&input), None);
+            assert!(explain(&input)
+                .ends_with("Part 2: not enough time, teaching the elephant takes 4 minutes."));
+        });
+    }
+
+    #[test]
+    fn test_schedule() {
+        let input = advent_of_code::read_file("examples", 16);
+        let valves = parse_input(&input);
+        let tunnels = Tunnels::new(&valves);
+        let (pressure, plan) = BranchAndBound::new(&tunnels).run(30, 0);
+        let steps = schedule(&valves, &tunnels, &plan, 30);
+
+        assert_eq!(
+            steps.iter().map(|step| step.pressure).sum::<u32>(),
+            pressure
+        );
+        let opened: Vec<(&str, u32)> = steps
+            .iter()
+            .map(|step| (step.valve.as_str(), step.minute))
+            .collect();
+        assert_eq!(
+            opened,
+            vec![
+                ("DD", 2),
+                ("BB", 5),
+                ("JJ", 9),
+                ("HH", 17),
+                ("EE", 21),
+                ("CC", 24)
+            ]
+        );
+        assert_eq!(steps[2].path, vec!["BB", "AA", "II", "JJ"]);
+        assert_eq!(
+            steps[0].to_string(),
+            "minute  2: open DD, releasing  560 (AA -> DD)"
+        );
+    }
+
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 16);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(1707));
     }
 }
@@ -0,0 +1,104 @@
+/*
+ * This file contains template code.
+ * There is no need to edit this file unless you want to change template functionality.
+ */
+use advent_of_code::encryption::{self, Cipher};
+use std::{fs, path::PathBuf, process};
+
+/// Folders and extensions of the files that are not committed in plaintext.
+const FILES: [(&str, &str); 3] = [
+    ("src/inputs", "txt"),
+    ("src/inputs", "answers"),
+    ("src/puzzles", "md"),
+];
+
+struct Args {
+    decrypt: bool,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        decrypt: args.contains(["-d", "--decrypt"]),
+    })
+}
+
+fn files_with_extension(folder: &str, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(folder) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+fn encrypt_all(cipher: &Cipher) -> Result<(), encryption::EncryptionError> {
+    for (folder, extension) in FILES {
+        for path in files_with_extension(folder, extension) {
+            if fs::metadata(&path).map_or(true, |m| m.len() == 0) {
+                println!("Skipped empty file \"{}\"", path.display());
+            } else if cipher.encrypt_file(&path)? {
+                println!(
+                    "Encrypted \"{}\"",
+                    encryption::encrypted_path(&path).display()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decrypt_all(cipher: &Cipher) -> Result<(), encryption::EncryptionError> {
+    for (folder, extension) in FILES {
+        for encrypted_path in files_with_extension(folder, encryption::EXTENSION) {
+            let path = encrypted_path.with_extension("");
+            if path.extension().is_none_or(|ext| ext != extension) {
+                continue;
+            }
+            if fs::read_to_string(&path).is_ok_and(|contents| !contents.trim().is_empty()) {
+                println!("Skipped existing file \"{}\"", path.display());
+                continue;
+            }
+            let plaintext = cipher.decrypt_file(&path)?;
+            fs::write(&path, plaintext)
+                .map_err(|e| encryption::EncryptionError::Io(path.clone(), e))?;
+            println!("Decrypted \"{}\"", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let cipher = match Cipher::from_env() {
+        Ok(cipher) => cipher,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    let result = if args.decrypt {
+        decrypt_all(&cipher)
+    } else {
+        encrypt_all(&cipher)
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+
+    println!("---");
+    println!("🎄 Done.");
+}
@@ -0,0 +1,82 @@
+/*
+ * This file contains template code.
+ * There is no need to edit this file unless you want to change template functionality.
+ */
+use advent_of_code::{
+    leaderboard::{self, Leaderboard, LeaderboardError},
+    progress::Date,
+};
+use std::{path::PathBuf, process};
+
+struct Args {
+    file: Option<PathBuf>,
+    id: Option<u64>,
+    year: Option<i32>,
+    day: Option<u8>,
+    base_url: Option<String>,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        id: args.opt_value_from_str("--id")?,
+        year: args.opt_value_from_str(["-y", "--year"])?,
+        day: args.opt_value_from_str(["-d", "--day"])?,
+        base_url: args.opt_value_from_str("--base-url")?,
+        file: args.opt_free_from_str()?,
+    })
+}
+
+fn load(args: &Args) -> Result<Leaderboard, LeaderboardError> {
+    if let Some(file) = &args.file {
+        return Leaderboard::read(file);
+    }
+
+    let id = match args.id {
+        Some(id) => id,
+        None => {
+            eprintln!(
+                "pass the path to an exported leaderboard or the leaderboard `--id` to fetch."
+            );
+            process::exit(1);
+        }
+    };
+    // events start in december, before that the latest leaderboard is last year's.
+    let year = args.year.unwrap_or_else(|| {
+        let today = Date::today();
+        if today.month == 12 {
+            today.year
+        } else {
+            today.year - 1
+        }
+    });
+    let base_url = args.base_url.clone().unwrap_or_else(leaderboard::base_url);
+    Leaderboard::fetch(&base_url, year, id, &leaderboard::session()?)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let leaderboard = match load(&args) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    println!("{}", leaderboard.render_standings());
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => leaderboard.days(),
+    };
+    for day in days {
+        println!("\n{}", leaderboard.render_day(day));
+    }
+}
@@ -16,8 +16,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     None
 }
 
+const FINGERPRINT: advent_of_code::fingerprint::Fingerprint = |_input| true;
+
 fn main() {
+    advent_of_code::examples::run_if_requested(DAY, part_one, part_two);
     let input = &advent_of_code::read_file("inputs", DAY);
+    advent_of_code::fingerprint::check(DAY, input, FINGERPRINT);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
 }
@@ -27,19 +31,23 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
+    fn test_fingerprint() {
         let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_one(&input), None);
+        assert!(FINGERPRINT(&input));
     }
 
     #[test]
-    fn test_part_two() {
-        let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_two(&input), None);
+    fn test_examples() {
+        advent_of_code::examples::assert_all(DAY, part_one, part_two);
     }
 }
 "###;
 
+const ANSWERS_TEMPLATE: &str = r###"# expected answers for the example input, empty answers are not checked.
+part_one:
+part_two:
+"###;
+
 fn parse_args() -> Result<u8, pico_args::Error> {
     let mut args = pico_args::Arguments::from_env();
     args.free_from_str()
@@ -66,6 +74,7 @@ fn main() {
 
     let input_path = format!("src/inputs/{}.txt", day_padded);
     let example_path = format!("src/examples/{}.txt", day_padded);
+    let answers_path = format!("src/examples/{}.answers", day_padded);
     let module_path = format!("src/bin/{}.rs", day_padded);
 
     let mut file = match safe_create_file(&module_path) {
@@ -106,6 +115,24 @@ fn main() {
         }
     }
 
+    let mut file = match safe_create_file(&answers_path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Failed to create answers file: {}", e);
+            process::exit(1);
+        }
+    };
+
+    match file.write_all(ANSWERS_TEMPLATE.as_bytes()) {
+        Ok(_) => {
+            println!("Created answers file \"{}\"", &answers_path);
+        }
+        Err(e) => {
+            eprintln!("Failed to write answers file: {}", e);
+            process::exit(1);
+        }
+    }
+
     println!("---");
     println!(
         "🎄 Type `cargo solve {}` to run your solution.",
@@ -0,0 +1,9 @@
+/*
+ * This file contains template code.
+ * There is no need to edit this file unless you want to change template functionality.
+ */
+use advent_of_code::progress::Progress;
+
+fn main() {
+    println!("{}", Progress::load().calendar());
+}
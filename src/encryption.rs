@@ -0,0 +1,262 @@
+/*
+ * Encrypted-at-rest inputs, accepted answers and puzzle descriptions.
+ * `cargo encrypt` writes `src/inputs/NN.txt.enc`, `src/inputs/NN.answers.enc` and
+ * `src/puzzles/NN.md.enc` next to the plaintext files. The `.enc` files can be committed, and
+ * `read_file` decrypts them when the plaintext is missing or empty.
+ * The key is read from the `AOC_INPUT_KEY` environment variable. It must be 32 random bytes written
+ * as 64 hex digits, e.g. from `openssl rand -hex 32`: the ciphertext may be public, so passphrases
+ * that could be brute-forced offline are rejected.
+ */
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+
+pub const KEY_VAR: &str = "AOC_INPUT_KEY";
+pub const EXTENSION: &str = "enc";
+
+const MAGIC: &[u8] = b"aoc-enc1";
+const NONCE_LENGTH: usize = 24;
+const KEY_LENGTH: usize = 32;
+
+#[derive(Debug)]
+pub enum EncryptionError {
+    MissingKey,
+    InvalidKey,
+    Io(PathBuf, io::Error),
+    InvalidFile(PathBuf),
+    DecryptionFailed(PathBuf),
+    KeyChanged(PathBuf),
+}
+
+impl Display for EncryptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncryptionError::MissingKey => write!(
+                f,
+                "environment variable {} is not set, it must contain the key the inputs were encrypted with.",
+                KEY_VAR
+            ),
+            EncryptionError::InvalidKey => write!(
+                f,
+                "{} must hold a key of {} random bytes as {} hex digits, generate one with `openssl rand -hex {}`.",
+                KEY_VAR,
+                KEY_LENGTH,
+                KEY_LENGTH * 2,
+                KEY_LENGTH
+            ),
+            EncryptionError::Io(path, e) => write!(f, "could not access \"{}\": {}", path.display(), e),
+            EncryptionError::InvalidFile(path) => {
+                write!(f, "\"{}\" is not an encrypted input file.", path.display())
+            }
+            EncryptionError::DecryptionFailed(path) => write!(
+                f,
+                "could not decrypt \"{}\": {} holds the wrong key or the file is corrupted.",
+                path.display(),
+                KEY_VAR
+            ),
+            EncryptionError::KeyChanged(path) => write!(
+                f,
+                "refusing to overwrite \"{}\": it does not decrypt with the key in {}. Delete it to encrypt the input again with this key.",
+                path.display(),
+                KEY_VAR
+            ),
+        }
+    }
+}
+
+pub struct Cipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl Cipher {
+    pub fn new(key: &[u8; KEY_LENGTH]) -> Cipher {
+        Cipher {
+            cipher: XChaCha20Poly1305::new(Key::from_slice(key)),
+        }
+    }
+
+    /// Parses a key written as 64 hex digits. Anything else is rejected, the key is used as is and
+    /// must not be guessable.
+    pub fn from_hex(text: &str) -> Result<Cipher, EncryptionError> {
+        let text = text.trim();
+        if text.len() != KEY_LENGTH * 2 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(EncryptionError::InvalidKey);
+        }
+        let mut key = [0; KEY_LENGTH];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16)
+                .map_err(|_| EncryptionError::InvalidKey)?;
+        }
+        Ok(Cipher::new(&key))
+    }
+
+    pub fn from_env() -> Result<Cipher, EncryptionError> {
+        match env::var(KEY_VAR) {
+            Ok(key) if !key.is_empty() => Cipher::from_hex(&key),
+            _ => Err(EncryptionError::MissingKey),
+        }
+    }
+
+    /// Encrypts `plaintext`. `name` is authenticated with it, so a file renamed to another day's
+    /// name fails to decrypt.
+    pub fn encrypt(&self, name: &str, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .expect("encryption failed");
+        [MAGIC, nonce.as_slice(), &ciphertext].concat()
+    }
+
+    pub fn decrypt(&self, name: &str, data: &[u8]) -> Option<Vec<u8>> {
+        let data = data.strip_prefix(MAGIC)?;
+        if data.len() < NONCE_LENGTH {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        self.cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .ok()
+    }
+
+    /// Decrypts the `.enc` file that belongs to the plaintext file `path`.
+    pub fn decrypt_file(&self, path: &Path) -> Result<String, EncryptionError> {
+        let encrypted_path = encrypted_path(path);
+        let data = fs::read(&encrypted_path)
+            .map_err(|e| EncryptionError::Io(encrypted_path.clone(), e))?;
+        if !data.starts_with(MAGIC) {
+            return Err(EncryptionError::InvalidFile(encrypted_path));
+        }
+        let plaintext = self
+            .decrypt(&file_name(path), &data)
+            .ok_or_else(|| EncryptionError::DecryptionFailed(encrypted_path.clone()))?;
+        String::from_utf8(plaintext).map_err(|_| EncryptionError::InvalidFile(encrypted_path))
+    }
+
+    /// Writes the `.enc` file for the plaintext file `path`. Returns `false` without touching the
+    /// `.enc` file if it already holds the same contents, so unchanged inputs do not show up in diffs.
+    /// Fails instead of overwriting an `.enc` file that does not decrypt with this key.
+    pub fn encrypt_file(&self, path: &Path) -> Result<bool, EncryptionError> {
+        let plaintext =
+            fs::read_to_string(path).map_err(|e| EncryptionError::Io(path.to_path_buf(), e))?;
+        match self.decrypt_file(path) {
+            Ok(existing) if existing == plaintext => return Ok(false),
+            Ok(_) => {}
+            Err(EncryptionError::Io(_, e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(EncryptionError::DecryptionFailed(encrypted_path))
+            | Err(EncryptionError::InvalidFile(encrypted_path)) => {
+                return Err(EncryptionError::KeyChanged(encrypted_path))
+            }
+            Err(e) => return Err(e),
+        }
+        let encrypted_path = encrypted_path(path);
+        fs::write(
+            &encrypted_path,
+            self.encrypt(&file_name(path), plaintext.as_bytes()),
+        )
+        .map_err(|e| EncryptionError::Io(encrypted_path, e))?;
+        Ok(true)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the path of the encrypted file for a plaintext file, e.g. `src/inputs/01.txt.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut encrypted = path.as_os_str().to_owned();
+    encrypted.push(".");
+    encrypted.push(EXTENSION);
+    PathBuf::from(encrypted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn cipher() -> Cipher {
+        Cipher::from_hex(KEY).unwrap()
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert!(Cipher::from_hex(KEY).is_ok());
+        assert!(Cipher::from_hex(&format!("{}\n", KEY.to_uppercase())).is_ok());
+        assert!(matches!(
+            Cipher::from_hex("secret"),
+            Err(EncryptionError::InvalidKey)
+        ));
+        assert!(Cipher::from_hex(&KEY[2..]).is_err());
+        assert!(Cipher::from_hex(&KEY.replace('a', "g")).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = cipher();
+        let data = cipher.encrypt("01.txt", b"1000\n2000\n");
+        assert_eq!(
+            cipher.decrypt("01.txt", &data),
+            Some(b"1000\n2000\n".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_decrypt_fails() {
+        let data = cipher().encrypt("01.txt", b"1000\n");
+        assert_eq!(Cipher::new(&[7; KEY_LENGTH]).decrypt("01.txt", &data), None);
+        assert_eq!(cipher().decrypt("02.txt", &data), None);
+        assert_eq!(cipher().decrypt("01.txt", b"1000\n"), None);
+    }
+
+    #[test]
+    fn test_encrypt_file_keeps_foreign_ciphertext() {
+        let folder = env::temp_dir().join(format!("aoc-encryption-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("01.txt");
+        fs::write(&path, "1000\n").unwrap();
+
+        assert!(cipher().encrypt_file(&path).unwrap());
+        assert!(!cipher().encrypt_file(&path).unwrap());
+        let written = fs::read(encrypted_path(&path)).unwrap();
+        assert!(matches!(
+            Cipher::new(&[7; KEY_LENGTH]).encrypt_file(&path),
+            Err(EncryptionError::KeyChanged(_))
+        ));
+        assert_eq!(fs::read(encrypted_path(&path)).unwrap(), written);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn test_encrypted_path() {
+        assert_eq!(
+            encrypted_path(Path::new("src/inputs/01.txt")),
+            PathBuf::from("src/inputs/01.txt.enc")
+        );
+    }
+}
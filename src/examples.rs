@@ -0,0 +1,265 @@
+/*
+ * Expected answers for example inputs.
+ * An example `src/examples/NN.txt` can declare its answers in a sidecar file `src/examples/NN.answers`:
+ *
+ *     # answers from the puzzle description
+ *     part_one: 24000
+ *     part_two: 45000
+ *
+ * Parts without a declared answer are skipped. Examples that do not belong to the day of their
+ * file name (e.g. a second example for day 9 in `99.txt`) declare their day with `day: 9`.
+ * Any other key overrides a puzzle parameter while the example runs, see `crate::params`.
+ */
+use std::{collections::BTreeMap, env, fmt::Display, fs, path::PathBuf, process};
+
+use crate::{params, ANSI_BOLD, ANSI_RESET};
+
+const ANSWERS_EXTENSION: &str = "answers";
+
+/// Keys of an answers file that describe the example itself rather than a puzzle parameter.
+const RESERVED_KEYS: [&str; 3] = ["day", "part_one", "part_two"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub day: u8,
+    pub values: BTreeMap<String, String>,
+}
+
+/// Parses `key: value` lines of an answers file. Blank lines and lines starting with `#` are ignored.
+pub fn parse_values(file_name: &str, text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("{}:{}: expected `key: value`", file_name, i + 1))?;
+        values.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    Ok(values)
+}
+
+impl Example {
+    /// Parses the contents of a sidecar file. `name` is the file stem of the example, e.g. `"01"`.
+    pub fn parse(name: &str, text: &str) -> Result<Example, String> {
+        let values = parse_values(&format!("{}.{}", name, ANSWERS_EXTENSION), text)?;
+
+        let day = match values.get("day") {
+            Some(day) => day.parse(),
+            None => name.parse(),
+        }
+        .map_err(|_| format!("{}.answers: could not determine the day", name))?;
+
+        Ok(Example {
+            name: name.to_string(),
+            day,
+            values,
+        })
+    }
+
+    /// Returns the declared value for `key`. Empty values count as not declared.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(key)
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.get("part_one"),
+            2 => self.get("part_two"),
+            _ => None,
+        }
+    }
+
+    /// The puzzle parameters the example overrides, i.e. all values except the reserved keys.
+    pub fn params(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.values
+            .iter()
+            .filter(|(key, _)| !RESERVED_KEYS.contains(&key.as_str()))
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.txt", self.name)
+    }
+
+    pub fn input(&self) -> String {
+        let path = examples_dir().join(self.file_name());
+        fs::read_to_string(path).expect("could not open example file")
+    }
+}
+
+fn examples_dir() -> PathBuf {
+    env::current_dir().unwrap().join("src").join("examples")
+}
+
+/// Returns all examples with a sidecar file for `day`, ordered by file name.
+pub fn for_day(day: u8) -> Vec<Example> {
+    let entries = match fs::read_dir(examples_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut examples: Vec<Example> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == ANSWERS_EXTENSION))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let text = fs::read_to_string(&path).expect("could not open answers file");
+            Example::parse(&name, &text).unwrap_or_else(|e| panic!("{}", e))
+        })
+        .filter(|example| example.day == day)
+        .collect();
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    examples
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed { expected: String, actual: String },
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub example: String,
+    pub part: u8,
+    pub outcome: Outcome,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outcome {
+            Outcome::Passed => write!(f, "{} part {}: ok", self.example, self.part),
+            Outcome::Failed { expected, actual } => write!(
+                f,
+                "{} part {}: FAILED, expected {} but got {}",
+                self.example, self.part, expected, actual
+            ),
+        }
+    }
+}
+
+fn check_part<T: Display>(
+    example: &Example,
+    input: &str,
+    part: u8,
+    solver: impl Fn(&str) -> Option<T>,
+) -> Option<Check> {
+    let expected = example.expected(part)?;
+    let actual = match params::with_overrides(example.params(), || solver(input)) {
+        Some(result) => result.to_string(),
+        None => "nothing".to_string(),
+    };
+    let outcome = if actual == expected {
+        Outcome::Passed
+    } else {
+        Outcome::Failed {
+            expected: expected.to_string(),
+            actual,
+        }
+    };
+    Some(Check {
+        example: example.file_name(),
+        part,
+        outcome,
+    })
+}
+
+/// Runs both parts against every example of `day` that declares an answer for them.
+pub fn check<A: Display, B: Display>(
+    day: u8,
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for example in for_day(day) {
+        let input = example.input();
+        checks.extend(check_part(&example, &input, 1, &part_one));
+        checks.extend(check_part(&example, &input, 2, &part_two));
+    }
+    checks
+}
+
+/// Test harness: panics with a list of failures if any example answer does not match.
+pub fn assert_all<A: Display, B: Display>(
+    day: u8,
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+) {
+    let failures: Vec<String> = check(day, part_one, part_two)
+        .iter()
+        .filter(|check| check.outcome != Outcome::Passed)
+        .map(|check| check.to_string())
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+/// If `--examples` was passed on the command line, checks all examples of `day`, prints the
+/// results and exits. Exits with a non-zero status if any example failed.
+pub fn run_if_requested<A: Display, B: Display>(
+    day: u8,
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+) {
+    if !pico_args::Arguments::from_env().contains("--examples") {
+        return;
+    }
+
+    let checks = check(day, part_one, part_two);
+    if checks.is_empty() {
+        println!("no example answers declared.");
+    }
+    for check in checks.iter() {
+        println!("🎄 {}{}{}", ANSI_BOLD, check, ANSI_RESET);
+    }
+
+    let failed = checks.iter().any(|check| check.outcome != Outcome::Passed);
+    process::exit(if failed { 1 } else { 0 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let example =
+            Example::parse("99", "# larger example\nday: 9\npart_one:\npart_two: 36\n").unwrap();
+        assert_eq!(example.day, 9);
+        assert_eq!(example.expected(1), None);
+        assert_eq!(example.expected(2), Some("36"));
+        assert_eq!(example.file_name(), "99.txt");
+
+        assert_eq!(example.params().count(), 0);
+
+        let example = Example::parse("15", "target_y: 10\npart_one: 26\n").unwrap();
+        assert_eq!(
+            example.params().collect::<Vec<_>>(),
+            vec![(&"target_y".to_string(), &"10".to_string())]
+        );
+
+        assert!(Example::parse("01", "part_one 24000").is_err());
+        assert!(Example::parse("larger", "part_one: 1").is_err());
+    }
+
+    #[test]
+    fn test_check_part() {
+        let example = Example::parse("01", "part_one: 3\npart_two: 4").unwrap();
+        let passed = check_part(&example, "1 2", 1, |_| Some(3)).unwrap();
+        assert_eq!(passed.outcome, Outcome::Passed);
+
+        let failed = check_part(&example, "1 2", 2, |_| None::<u32>).unwrap();
+        assert_eq!(
+            failed.outcome,
+            Outcome::Failed {
+                expected: "4".to_string(),
+                actual: "nothing".to_string()
+            }
+        );
+    }
+}
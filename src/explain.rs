@@ -0,0 +1,21 @@
+/*
+ * `--explain` mode for solutions.
+ * Some days can show how they got to an answer, e.g. the schedule behind a score or the steps of a
+ * comparison. The explanation is built after `solve!`, so it never affects timings.
+ * Example: `cargo solve 16 -- --explain`.
+ */
+use crate::{ANSI_BOLD, ANSI_RESET};
+
+/// Whether `--explain` was passed on the command line.
+pub fn requested() -> bool {
+    pico_args::Arguments::from_env().contains("--explain")
+}
+
+/// Prints the explanation returned by `explain` if `--explain` was passed on the command line.
+/// Meant to be called from a solution's `main` after `solve!`.
+pub fn explain_from_args(explain: impl FnOnce() -> String) {
+    if requested() {
+        println!("🎄 {}Explanation{} 🎄", ANSI_BOLD, ANSI_RESET);
+        println!("{}", explain());
+    }
+}
@@ -0,0 +1,217 @@
+/*
+ * Quick input-shape checks used to catch inputs pasted into the wrong day's file.
+ * Every day declares its `FINGERPRINT` constant next to its solution and checks its input with
+ * `check(DAY, input, FINGERPRINT)`. The predicates live here and are registered in `FINGERPRINTS`,
+ * so a mismatch can name the day an input belongs to. Fingerprints should be cheap and only look
+ * at the overall shape of an input, they are not meant to validate it.
+ */
+
+/// Returns whether an input has the overall shape of the day's puzzle input.
+pub type Fingerprint = fn(&str) -> bool;
+
+pub const DAY_01: Fingerprint = |input| {
+    input.lines().any(|line| line.is_empty())
+        && all_lines(input, |line| line.chars().all(|c| c.is_ascii_digit()))
+};
+
+pub const DAY_02: Fingerprint = |input| {
+    all_non_empty_lines(input, |line| {
+        let bytes = line.as_bytes();
+        bytes.len() == 3
+            && (b'A'..=b'C').contains(&bytes[0])
+            && bytes[1] == b' '
+            && (b'X'..=b'Z').contains(&bytes[2])
+    })
+};
+
+pub const DAY_03: Fingerprint = |input| {
+    input.lines().count() > 1
+        && all_non_empty_lines(input, |line| {
+            line.len() % 2 == 0 && line.chars().all(|c| c.is_ascii_alphabetic())
+        })
+        && input
+            .chars()
+            .any(|c| c.is_ascii_uppercase() && c != 'S' && c != 'E')
+        && input.chars().any(|c| c.is_ascii_lowercase())
+};
+
+pub const DAY_04: Fingerprint = |input| {
+    all_non_empty_lines(input, |line| {
+        line.split(',').count() == 2
+            && line.split(',').all(|range| {
+                range.split('-').count() == 2 && range.split('-').all(|n| n.parse::<u32>().is_ok())
+            })
+    })
+};
+
+pub const DAY_05: Fingerprint = |input| {
+    input.lines().next().is_some_and(|line| line.contains('['))
+        && input.lines().any(|line| line.starts_with("move "))
+};
+
+pub const DAY_06: Fingerprint = |input| {
+    input.trim_end().lines().count() == 1
+        && input.trim_end().chars().all(|c| c.is_ascii_lowercase())
+};
+
+pub const DAY_07: Fingerprint = |input| first_line_starts_with(input, "$ cd");
+
+pub const DAY_08: Fingerprint = |input| {
+    let width = input.lines().next().map_or(0, |line| line.len());
+    width > 0
+        && all_non_empty_lines(input, |line| {
+            line.len() == width && line.chars().all(|c| c.is_ascii_digit())
+        })
+};
+
+pub const DAY_09: Fingerprint = |input| {
+    all_non_empty_lines(input, |line| {
+        matches!(line.split_once(' '), Some((dir, steps))
+            if ["U", "D", "L", "R"].contains(&dir) && steps.parse::<u32>().is_ok())
+    })
+};
+
+pub const DAY_10: Fingerprint = |input| {
+    all_non_empty_lines(input, |line| {
+        line == "noop"
+            || line
+                .strip_prefix("addx ")
+                .is_some_and(|n| n.parse::<i32>().is_ok())
+    })
+};
+
+pub const DAY_11: Fingerprint = |input| first_line_starts_with(input, "Monkey 0:");
+
+pub const DAY_12: Fingerprint = |input| {
+    let width = input.lines().next().map_or(0, |line| line.len());
+    width > 0
+        && input.matches('S').count() == 1
+        && input.matches('E').count() == 1
+        && all_non_empty_lines(input, |line| {
+            line.len() == width
+                && line
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c == 'S' || c == 'E')
+        })
+};
+
+pub const DAY_13: Fingerprint = |input| {
+    first_line_starts_with(input, "[")
+        && all_non_empty_lines(input, |line| line.starts_with('[') && line.ends_with(']'))
+};
+
+pub const DAY_14: Fingerprint = |input| all_non_empty_lines(input, |line| line.contains(" -> "));
+
+pub const DAY_15: Fingerprint = |input| first_line_starts_with(input, "Sensor at x=");
+
+pub const DAY_16: Fingerprint = |input| first_line_starts_with(input, "Valve ");
+
+const FINGERPRINTS: [(u8, Fingerprint); 16] = [
+    (1, DAY_01),
+    (2, DAY_02),
+    (3, DAY_03),
+    (4, DAY_04),
+    (5, DAY_05),
+    (6, DAY_06),
+    (7, DAY_07),
+    (8, DAY_08),
+    (9, DAY_09),
+    (10, DAY_10),
+    (11, DAY_11),
+    (12, DAY_12),
+    (13, DAY_13),
+    (14, DAY_14),
+    (15, DAY_15),
+    (16, DAY_16),
+];
+
+pub fn first_line_starts_with(input: &str, prefix: &str) -> bool {
+    input
+        .lines()
+        .next()
+        .is_some_and(|line| line.starts_with(prefix))
+}
+
+pub fn all_lines(input: &str, predicate: impl Fn(&str) -> bool) -> bool {
+    input.lines().all(predicate)
+}
+
+pub fn all_non_empty_lines(input: &str, predicate: impl Fn(&str) -> bool) -> bool {
+    input.lines().filter(|line| !line.is_empty()).all(predicate)
+}
+
+/// Returns whether `input` matches the registered fingerprint of `day`, or `None` if the day
+/// has none.
+pub fn matches(day: u8, input: &str) -> Option<bool> {
+    FINGERPRINTS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, fingerprint)| fingerprint(input))
+}
+
+/// Returns all days whose registered fingerprint matches `input`.
+pub fn guess_days(input: &str) -> Vec<u8> {
+    FINGERPRINTS
+        .iter()
+        .filter(|(_, fingerprint)| fingerprint(input))
+        .map(|(day, _)| *day)
+        .collect()
+}
+
+/// Warns if the input of `day` does not match its fingerprint and names the days it looks like,
+/// e.g. because another day's input was pasted into `src/inputs/NN.txt`.
+pub fn check(day: u8, input: &str, fingerprint: Fingerprint) {
+    if fingerprint(input) {
+        return;
+    }
+    let other_days: Vec<String> = guess_days(input)
+        .iter()
+        .filter(|d| **d != day)
+        .map(|d| d.to_string())
+        .collect();
+    if other_days.is_empty() {
+        eprintln!(
+            "warning: src/inputs/{:02}.txt does not look like the input for day {}.",
+            day, day
+        );
+    } else {
+        eprintln!(
+            "warning: src/inputs/{:02}.txt looks like the input for day {}, not day {}.",
+            day,
+            other_days.join(" or "),
+            day
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_match_only_their_day() {
+        for day in 1..=16 {
+            let input = crate::read_file("examples", day);
+            assert_eq!(guess_days(&input), vec![day], "example for day {}", day);
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(
+            matches(15, "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n"),
+            Some(true)
+        );
+        assert_eq!(matches(16, "Sensor at x=2, y=18"), Some(false));
+        assert_eq!(matches(25, ""), None);
+    }
+
+    #[test]
+    fn test_helpers() {
+        let input = "Sensor at x=2\n\nSensor at x=9\n";
+        assert!(first_line_starts_with(input, "Sensor at"));
+        assert!(!first_line_starts_with("", "Sensor at"));
+        assert!(all_non_empty_lines(input, |line| line.starts_with("Sensor")));
+        assert!(!all_lines(input, |line| line.starts_with("Sensor")));
+    }
+}
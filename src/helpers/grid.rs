@@ -0,0 +1,439 @@
+/*
+ * Rectangular grid with flat storage.
+ * Cells are addressed by `(x, y)` positions. The grid can be moved with `with_origin`, so inputs that
+ * use negative or large coordinates can be indexed directly instead of shifting every position.
+ */
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use super::point::Point2;
+
+/// An `(x, y)` position, `y` grows downwards.
+pub type Pos = (isize, isize);
+
+pub const NEIGHBOURS_4: [Pos; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const NEIGHBOURS_8: [Pos; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidChar {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid is empty."),
+            GridError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cells, expected {} like the first row.",
+                row + 1,
+                found,
+                expected
+            ),
+            GridError::InvalidChar { row, column, found } => write!(
+                f,
+                "unexpected '{}' at row {}, column {}.",
+                found,
+                row + 1,
+                column + 1
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    origin: Pos,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics if `width` or `height` is 0, so transposed and rotated grids are never empty either.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        assert!(width > 0, "a grid needs at least one column");
+        assert!(height > 0, "a grid needs at least one row");
+        Grid {
+            width,
+            height,
+            origin: (0, 0),
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from rows of equal length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, GridError> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if width == 0 {
+            return Err(GridError::Empty);
+        }
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::Ragged {
+                    row: i,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            origin: (0, 0),
+            cells,
+        })
+    }
+
+    /// Parses a map of characters, `cell` returns `None` for characters that are not allowed.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>, GridError> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| {
+                        cell(c).ok_or(GridError::InvalidChar {
+                            row,
+                            column,
+                            found: c,
+                        })
+                    })
+                    .collect::<Result<Vec<T>, GridError>>()
+            })
+            .collect::<Result<Vec<Vec<T>>, GridError>>()?;
+        Grid::from_rows(rows)
+    }
+
+    /// Moves the grid so that its top left cell is at `origin`.
+    pub fn with_origin(mut self, origin: Pos) -> Grid<T> {
+        self.origin = origin;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        dx >= 0 && dy >= 0 && (dx as usize) < self.width && (dy as usize) < self.height
+    }
+
+    /// Index of `pos` in the flat storage.
+    pub fn index_of(&self, pos: Pos) -> Option<usize> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let (x, y) = (
+            (pos.0 - self.origin.0) as usize,
+            (pos.1 - self.origin.1) as usize,
+        );
+        Some(y * self.width + x)
+    }
+
+    pub fn pos_of(&self, index: usize) -> Pos {
+        (
+            (index % self.width) as isize + self.origin.0,
+            (index / self.width) as isize + self.origin.1,
+        )
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.cells.len()).map(|i| self.pos_of(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.pos_of(i), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Neighbours in the grid along the axes.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_4)
+    }
+
+    /// Neighbours in the grid along the axes and diagonals.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_8)
+    }
+
+    fn neighbours<'a>(&'a self, (x, y): Pos, offsets: &'a [Pos]) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|pos| self.contains(*pos))
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Cells of row `y`, left to right, `None` if the row is outside of the grid.
+    pub fn row(&self, y: isize) -> Option<impl Iterator<Item = &T>> {
+        let start = self.index_of((self.origin.0, y))?;
+        Some(self.cells[start..start + self.width].iter())
+    }
+
+    /// Cells of column `x`, top to bottom, `None` if the column is outside of the grid.
+    pub fn column(&self, x: isize) -> Option<impl Iterator<Item = &T>> {
+        let start = self.index_of((x, self.origin.1))?;
+        Some(self.cells[start..].iter().step_by(self.width))
+    }
+
+    /// Cells from `pos` (exclusive) in `direction` until the edge of the grid.
+    pub fn ray(&self, pos: Pos, direction: Pos) -> impl Iterator<Item = (Pos, &T)> {
+        (1..)
+            .map(move |step| (pos.0 + direction.0 * step, pos.1 + direction.1 * step))
+            .map_while(|pos| self.get(pos).map(|cell| (pos, cell)))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            origin: self.origin,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, the origin is mirrored as well.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            origin: (self.origin.1, self.origin.0),
+            cells: (0..self.width)
+                .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                .map(|(x, y)| self.cells[y * self.width + x].clone())
+                .collect(),
+        }
+    }
+
+    /// Rotates the grid by 90 degrees clockwise, the rotated grid starts at `(0, 0)`.
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let mut rotated = self.transpose().with_origin((0, 0));
+        for row in rotated.cells.chunks_mut(self.height) {
+            row.reverse();
+        }
+        rotated
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise, the rotated grid starts at `(0, 0)`.
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let transposed = self.transpose();
+        let rows: Vec<Vec<T>> = transposed.rows().rev().map(|row| row.to_vec()).collect();
+        Grid::from_rows(rows).unwrap()
+    }
+
+    /// Renders one character per cell and one line per row.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.index_of(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!("{:?} is outside of the grid", pos),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.index_of(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{:?} is outside of the grid", pos),
+        }
+    }
+}
+
+impl<T> Index<Point2<isize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point2<isize>) -> &T {
+        &self[Pos::from(point)]
+    }
+}
+
+impl<T> IndexMut<Point2<isize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point2<isize>) -> &mut T {
+        &mut self[Pos::from(point)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], 'e');
+        assert_eq!(grid[Point2::new(2, 0)], 'c');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(
+            Grid::parse("ab\nc\n", Some),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Grid::parse("12\n3x\n", |c| c.to_digit(10))
+                .unwrap_err()
+                .to_string(),
+            "unexpected 'x' at row 2, column 2."
+        );
+    }
+
+    #[test]
+    fn test_origin() {
+        let mut grid = Grid::new(3, 3, '.').with_origin((-1, -1));
+        grid[(-1, -1)] = '#';
+        assert_eq!(grid.cells()[0], '#');
+        assert!(grid.contains((1, 1)));
+        assert!(!grid.contains((2, 0)));
+        assert_eq!(grid.pos_of(8), (1, 1));
+        assert_eq!(
+            grid.neighbours4((-1, -1)).collect::<Vec<_>>(),
+            vec![(0, -1), (-1, 0)]
+        );
+        assert_eq!(grid.neighbours8((0, 0)).count(), 8);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = example();
+        assert_eq!(grid.row(1).unwrap().collect::<String>(), "def");
+        assert_eq!(grid.column(2).unwrap().collect::<String>(), "cf");
+        assert_eq!(
+            grid.ray((0, 0), (1, 0))
+                .map(|(_, c)| *c)
+                .collect::<String>(),
+            "bc"
+        );
+        assert_eq!(grid.ray((0, 1), (1, -1)).count(), 1);
+    }
+
+    #[test]
+    fn test_out_of_range_rows_and_columns() {
+        let grid = example().with_origin((-1, 5));
+        assert_eq!(grid.row(5).unwrap().collect::<String>(), "abc");
+        assert_eq!(grid.column(-1).unwrap().collect::<String>(), "ad");
+        assert!(grid.row(4).is_none());
+        assert!(grid.row(7).is_none());
+        assert!(grid.row(-1).is_none());
+        assert!(grid.column(-2).is_none());
+        assert!(grid.column(2).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "a grid needs at least one column")]
+    fn test_zero_width() {
+        Grid::new(0, 3, '.');
+    }
+
+    #[test]
+    #[should_panic(expected = "a grid needs at least one row")]
+    fn test_zero_height() {
+        Grid::new(3, 0, '.');
+    }
+
+    #[test]
+    fn test_empty_rows() {
+        assert!(matches!(
+            Grid::<char>::from_rows(Vec::new()),
+            Err(GridError::Empty)
+        ));
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = example();
+        assert_eq!(grid.transpose().render(|c| *c), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().render(|c| *c), "da\neb\nfc");
+        assert_eq!(grid.rotate_counter_clockwise().render(|c| *c), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+}
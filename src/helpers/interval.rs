@@ -0,0 +1,239 @@
+/*
+ * Inclusive integer intervals and sets of merged intervals.
+ * An `IntervalSet` keeps its intervals sorted, disjoint and non-adjacent, so `1..=3` and `4..=6`
+ * are stored as `1..=6` and the covered length is the sum of the interval lengths.
+ */
+use std::{fmt::Display, iter::FromIterator};
+
+use num::PrimInt;
+
+/// The integers from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// Panics if `start > end`, intervals are never empty.
+    pub fn new(start: T, end: T) -> Interval<T> {
+        assert!(start <= end, "interval start is after its end");
+        Interval { start, end }
+    }
+
+    /// Number of integers in the interval.
+    pub fn len(&self) -> T {
+        self.end - self.start + T::one()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether `other` lies completely inside the interval.
+    pub fn covers(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Whether `other` starts right after the end of the interval, or earlier.
+    fn reaches(&self, other: &Interval<T>) -> bool {
+        self.end
+            .checked_add(&T::one())
+            .is_none_or(|next| other.start <= next)
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Sorts and merges overlapping or adjacent intervals.
+    fn merged(mut intervals: Vec<Interval<T>>) -> IntervalSet<T> {
+        intervals.sort_unstable();
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.reaches(&interval) => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    /// Sorted, disjoint and non-adjacent intervals of the set.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers covered by the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::merged(intervals);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::merged(
+            self.intervals
+                .iter()
+                .chain(other.intervals.iter())
+                .copied()
+                .collect(),
+        )
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let (a, b) = (&self.intervals, &other.intervals);
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = Vec::new();
+        while i < a.len() && j < b.len() {
+            intervals.extend(a[i].intersection(&b[j]));
+            if a[i].end < b[j].end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// Integers of the set that are not in `other`.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        for interval in &self.intervals {
+            let mut start = Some(interval.start);
+            for removed in other.intervals.iter().filter(|r| r.overlaps(interval)) {
+                let Some(current) = start else { break };
+                if removed.start > current {
+                    intervals.push(Interval::new(current, removed.start - T::one()));
+                }
+                start = removed
+                    .end
+                    .checked_add(&T::one())
+                    .filter(|_| removed.end < interval.end);
+            }
+            if let Some(current) = start {
+                intervals.push(Interval::new(current, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// Parts of `bounds` that are not covered by the set.
+    pub fn gaps(&self, bounds: Interval<T>) -> IntervalSet<T> {
+        IntervalSet::from(bounds).difference(self)
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> IntervalSet<T> {
+        IntervalSet {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> IntervalSet<T> {
+        IntervalSet::merged(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2, 8);
+        assert_eq!(a.len(), 7);
+        assert!(a.covers(&Interval::new(3, 7)));
+        assert!(!a.covers(&Interval::new(6, 9)));
+        assert!(a.overlaps(&Interval::new(8, 9)));
+        assert!(!a.overlaps(&Interval::new(9, 9)));
+        assert_eq!(
+            a.intersection(&Interval::new(-4, 3)),
+            Some(Interval::new(2, 3))
+        );
+        assert_eq!(a.to_string(), "2..=8");
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = set(&[(5, 7), (-2, 0), (1, 2), (6, 10), (12, 12)]);
+        assert_eq!(merged, set(&[(-2, 2), (5, 10), (12, 12)]));
+        assert_eq!(merged.len(), 12);
+        assert!(merged.contains(-2));
+        assert!(!merged.contains(4));
+        assert!(merged.contains(12));
+
+        let mut inserted = merged.clone();
+        inserted.insert(Interval::new(3, 4));
+        assert_eq!(inserted, set(&[(-2, 10), (12, 12)]));
+        assert_eq!(set(&[(i32::MAX - 1, i32::MAX), (0, 1)]).len(), 4);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19)]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(
+            a.gaps(Interval::new(-5, 35)),
+            set(&[(-5, -1), (11, 19), (31, 35)])
+        );
+    }
+}
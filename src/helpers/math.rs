@@ -0,0 +1,123 @@
+/*
+ * Number theory helpers.
+ * Modular arithmetic works on `i64`/`u64` and uses 128 bit intermediates, so products of two
+ * residues never overflow.
+ */
+use num::{CheckedAdd, CheckedMul, Integer, One, Zero};
+
+/// Greatest common divisor of all `values`, `0` for an empty slice.
+pub fn gcd<T: Integer + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, value| acc.gcd(value))
+}
+
+/// Least common multiple of all `values`, `1` for an empty slice.
+pub fn lcm<T: Integer + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::one(), |acc, value| acc.lcm(value))
+}
+
+/// Sum of `values`, `None` if it overflows.
+pub fn checked_sum<T: CheckedAdd + Zero>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::zero(), |acc, value| acc.checked_add(&value))
+}
+
+/// Product of `values`, `None` if it overflows.
+pub fn checked_product<T: CheckedMul + One>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::one(), |acc, value| acc.checked_mul(&value))
+}
+
+/// `base^exponent mod modulus` by repeated squaring. Panics if `modulus` is 0.
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "mod_pow needs a positive modulus");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, `None` if `a` and `modulus` are not coprime.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let extended = a.rem_euclid(modulus).extended_gcd(&modulus);
+    (extended.gcd == 1).then(|| extended.x.rem_euclid(modulus))
+}
+
+/// Chinese remainder theorem: combines congruences `x ≡ remainder (mod modulus)` into a single
+/// `(remainder, modulus)` pair, where the modulus is the lcm of all moduli. Moduli must be positive
+/// but don't need to be coprime, remainders may be negative or larger than their modulus. `None`
+/// is returned if the congruences contradict each other or the lcm overflows.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    congruences
+        .iter()
+        .try_fold((0_i64, 1_i64), |(r1, m1), &(r2, m2)| {
+            let extended = m1.extended_gcd(&m2);
+            let g = extended.gcd as i128;
+            let difference = r2.rem_euclid(m2) as i128 - r1 as i128;
+            if difference % g != 0 {
+                return None;
+            }
+            let modulus = (m1 / extended.gcd).checked_mul(m2)?;
+            // r1 + m1 * k solves both congruences for k = (r2 - r1) / g * x (mod m2 / g).
+            let step = m2 as i128 / g;
+            let k =
+                (difference / g).rem_euclid(step) * (extended.x as i128).rem_euclid(step) % step;
+            let remainder = (r1 as i128 + m1 as i128 * k).rem_euclid(modulus as i128);
+            Some((remainder as i64, modulus))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(&[12, 18, 30]), 6);
+        assert_eq!(gcd::<u32>(&[]), 0);
+        assert_eq!(lcm(&[4_u64, 6, 10]), 60);
+        assert_eq!(lcm(&[23, 19, 13, 17]), 96577);
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(checked_sum([1_u8, 2, 3]), Some(6));
+        assert_eq!(checked_sum([200_u8, 100]), None);
+        assert_eq!(checked_product([u64::MAX, 1]), Some(u64::MAX));
+        assert_eq!(checked_product([u64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "positive modulus")]
+    fn test_mod_pow_zero_modulus() {
+        mod_pow(5, 3, 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(-1, 3), (12, 5)]), Some((2, 15)));
+        assert_eq!(crt(&[(i64::MIN, 3), (i64::MAX, 4)]), Some((7, 12)));
+    }
+}
@@ -0,0 +1,206 @@
+/*
+ * nom combinators for common input shapes.
+ * Example: `parse_all(lines(number_list::<i32>), input)` parses lines of comma-separated numbers and
+ * reports the line and column of the first unparsed character if the input does not match.
+ */
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    branch::alt,
+    character::complete::{char, digit1, line_ending, multispace0, none_of, space0},
+    combinator::{cut, map_res, opt, recognize},
+    multi::{many1, separated_list1},
+    sequence::{pair, separated_pair, terminated},
+    IResult,
+};
+
+/// Parses an unsigned integer, e.g. `42`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Parses an integer with an optional sign, e.g. `-42` or `+42`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(
+        recognize(pair(opt(alt((char('-'), char('+')))), digit1)),
+        |number: &str| number.trim_start_matches('+').parse(),
+    )(input)
+}
+
+/// Parses comma-separated signed integers, e.g. `1,-2, 3`.
+pub fn number_list<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(pair(char(','), space0), signed)(input)
+}
+
+/// Like `separated_list1`, but the list only ends at the end of the input or at a blank line.
+/// Any other item after a separator must parse, so errors point into the item instead of at its start.
+fn separated_items<'a, O, OS, F, S>(
+    mut separator: S,
+    mut parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+    S: FnMut(&'a str) -> IResult<&'a str, OS>,
+{
+    move |input| {
+        let (mut input, first) = parser(input)?;
+        let mut items = vec![first];
+        loop {
+            let rest = match separator(input) {
+                Ok((rest, _)) => rest,
+                Err(nom::Err::Error(_)) => return Ok((input, items)),
+                Err(e) => return Err(e),
+            };
+            if rest.is_empty() || line_ending::<_, nom::error::Error<&str>>(rest).is_ok() {
+                return Ok((input, items));
+            }
+            let (rest, item) = cut(&mut parser)(rest)?;
+            items.push(item);
+            input = rest;
+        }
+    }
+}
+
+/// Applies `parser` to every line. A trailing line ending is left for `parse_all` to skip.
+pub fn lines<'a, O, F>(parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_items(line_ending, parser)
+}
+
+/// Applies `parser` to every group of a blank-line-separated input, e.g. `groups(lines(unsigned))`.
+pub fn groups<'a, O, F>(parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_items(pair(line_ending, line_ending), parser)
+}
+
+/// Parses `key: value`, e.g. `key_value(alpha1, unsigned)` for `Time: 30`.
+pub fn key_value<'a, K, V, FK, FV>(
+    key: FK,
+    value: FV,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V)>
+where
+    FK: FnMut(&'a str) -> IResult<&'a str, K>,
+    FV: FnMut(&'a str) -> IResult<&'a str, V>,
+{
+    separated_pair(key, terminated(char(':'), space0), value)
+}
+
+/// Parses a map of characters into rows.
+pub fn char_grid(input: &str) -> IResult<&str, Vec<Vec<char>>> {
+    lines(many1(none_of("\r\n")))(input)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Locates `remaining`, a suffix of `input`, in `input`.
+    fn at(input: &str, remaining: &str) -> ParseError {
+        let consumed = &input[..input.len() - remaining.len()];
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            found: remaining.lines().next().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.found.is_empty() {
+            write!(
+                f,
+                "parse error at line {}, column {}: unexpected end of line",
+                self.line, self.column
+            )
+        } else {
+            write!(
+                f,
+                "parse error at line {}, column {}: unexpected \"{}\"",
+                self.line, self.column, self.found
+            )
+        }
+    }
+}
+
+/// Runs `parser` on the whole input. Trailing whitespace is skipped, any other unparsed input is an error.
+pub fn parse_all<'a, O, F>(mut parser: F, input: &'a str) -> Result<O, ParseError>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    match terminated(&mut parser, multispace0)(input) {
+        Ok(("", output)) => Ok(output),
+        Ok((remaining, _)) => Err(ParseError::at(input, remaining)),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::at(input, e.input)),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(input, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{bytes::complete::tag, character::complete::alpha1};
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(unsigned::<u32>("42,"), Ok((",", 42)));
+        assert!(unsigned::<u32>("-42").is_err());
+        assert!(unsigned::<u8>("256").is_err());
+        assert_eq!(signed::<i32>("-42"), Ok(("", -42)));
+        assert_eq!(signed::<i32>("+42"), Ok(("", 42)));
+        assert_eq!(number_list::<i64>("1,-2, 3\n"), Ok(("\n", vec![1, -2, 3])));
+    }
+
+    #[test]
+    fn test_lines_and_groups() {
+        let input = "1\n2\n\n3\n";
+        assert_eq!(
+            parse_all(groups(lines(unsigned::<u32>)), input),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+        assert_eq!(
+            parse_all(char_grid, "#.\n.#\n"),
+            Ok(vec![vec!['#', '.'], vec!['.', '#']])
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(
+            key_value(alpha1, unsigned::<u32>)("Time: 30"),
+            Ok(("", ("Time", 30)))
+        );
+    }
+
+    #[test]
+    fn test_parse_all_errors() {
+        let parser = lines(separated_pair(unsigned::<u32>, tag("-"), unsigned::<u32>));
+        assert_eq!(
+            parse_all(parser, "2-4\n6-x\n"),
+            Err(ParseError {
+                line: 2,
+                column: 3,
+                found: "x".to_string()
+            })
+        );
+        assert_eq!(
+            parse_all(groups(lines(unsigned::<u32>)), "1\n2\n\n3\n4a\n")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 5, column 2: unexpected \"a\""
+        );
+        assert_eq!(
+            parse_all(unsigned::<u32>, "12a").unwrap_err().to_string(),
+            "parse error at line 1, column 3: unexpected \"a\""
+        );
+    }
+}
@@ -0,0 +1,318 @@
+/*
+ * 2D and 3D points and grid directions.
+ * Points work with any integer type. `Direction` follows the grid convention where `y` grows
+ * downwards, so `Direction::Up.offset()` is `(0, -1)`.
+ */
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use num::Signed;
+
+use super::grid::Pos;
+
+fn abs_diff<T: PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// `std::cmp::max` for types that are only `PartialOrd`.
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Point2<T> {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    pub fn manhattan(&self, other: &Point2<T>) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Number of king moves between the points.
+    pub fn chebyshev(&self, other: &Point2<T>) -> T {
+        max(abs_diff(self.x, other.x), abs_diff(self.y, other.y))
+    }
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    /// Unit step towards the sign of each coordinate, e.g. `(3, -2)` becomes `(1, -1)`.
+    pub fn signum(&self) -> Point2<T> {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl<T: TryInto<usize> + Copy> Point2<T> {
+    /// Index into row-major storage of a grid with `width` columns, `None` if the point is outside.
+    pub fn to_index(&self, width: usize) -> Option<usize> {
+        let x: usize = self.x.try_into().ok()?;
+        let y: usize = self.y.try_into().ok()?;
+        (x < width).then_some(y * width + x)
+    }
+}
+
+impl<T: TryFrom<usize>> Point2<T> {
+    pub fn from_index(index: usize, width: usize) -> Option<Point2<T>> {
+        Some(Point2::new(
+            T::try_from(index % width).ok()?,
+            T::try_from(index / width).ok()?,
+        ))
+    }
+}
+
+impl From<Pos> for Point2<isize> {
+    fn from((x, y): Pos) -> Point2<isize> {
+        Point2::new(x, y)
+    }
+}
+
+impl From<Point2<isize>> for Pos {
+    fn from(point: Point2<isize>) -> Pos {
+        (point.x, point.y)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan(&self, other: &Point3<T>) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Point3<T>) -> T {
+        max(
+            max(abs_diff(self.x, other.x), abs_diff(self.y, other.y)),
+            abs_diff(self.z, other.z),
+        )
+    }
+}
+
+impl<T: Signed + Copy> Point3<T> {
+    pub fn signum(&self) -> Point3<T> {
+        Point3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// Implements component-wise `+`, `-`, negation and scalar `*` for a point type.
+macro_rules! impl_point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = $point<T>;
+
+            fn add(self, other: $point<T>) -> $point<T> {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = $point<T>;
+
+            fn sub(self, other: $point<T>) -> $point<T> {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: $point<T>) {
+                $(self.$field += other.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: $point<T>) {
+                $(self.$field -= other.$field;)+
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $point<T> {
+            type Output = $point<T>;
+
+            fn mul(self, factor: T) -> $point<T> {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = $point<T>;
+
+            fn neg(self) -> $point<T> {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2 { x, y });
+impl_point_ops!(Point3 { x, y, z });
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting at `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Parses `U`, `D`, `L`, `R` or `^`, `v`, `<`, `>`.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'U' | '^' => Some(Direction::Up),
+            'R' | '>' => Some(Direction::Right),
+            'D' | 'v' => Some(Direction::Down),
+            'L' | '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// Direction of a unit step along an axis, e.g. `(0, 1)` is `Down`.
+    pub fn from_offset<T: Signed + Copy>(offset: Point2<T>) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| direction.offset::<T>() == offset)
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn offset<T: Signed>(self) -> Point2<T> {
+        match self {
+            Direction::Up => Point2::new(T::zero(), -T::one()),
+            Direction::Right => Point2::new(T::one(), T::zero()),
+            Direction::Down => Point2::new(T::zero(), T::one()),
+            Direction::Left => Point2::new(-T::one(), T::zero()),
+        }
+    }
+
+    pub fn to_arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Direction, String> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Direction::from_char), chars.next()) {
+            (Some(direction), None) => Ok(direction),
+            _ => Err(format!("\"{}\" is not a direction", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point2() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(4, 2);
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(b - a, Point2::new(3, 4));
+        assert_eq!(a * 3, Point2::new(3, -6));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!((b - a).signum(), Point2::new(1, 1));
+        assert_eq!(Point2::new(3_u32, 5).manhattan(&Point2::new(5, 1)), 6);
+    }
+
+    #[test]
+    fn test_point3() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(-1, 5, 3);
+        assert_eq!(a - b, Point3::new(2, -3, 0));
+        assert_eq!(a.manhattan(&b), 5);
+        assert_eq!(a.chebyshev(&b), 3);
+    }
+
+    #[test]
+    fn test_index_conversion() {
+        assert_eq!(Point2::new(2, 1).to_index(3), Some(5));
+        assert_eq!(Point2::new(-1, 1).to_index(3), None);
+        assert_eq!(Point2::new(3, 0).to_index(3), None);
+        assert_eq!(Point2::<i32>::from_index(5, 3), Some(Point2::new(2, 1)));
+        assert_eq!(Pos::from(Point2::new(-1, 2)), (-1, 2));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!("<".parse(), Ok(Direction::Left));
+        assert!("UD".parse::<Direction>().is_err());
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::Down.offset::<i32>(), Point2::new(0, 1));
+        assert_eq!(
+            Direction::from_offset(Point2::new(-1_i64, 0)),
+            Some(Direction::Left)
+        );
+        assert_eq!(Direction::from_offset(Point2::new(1, 1)), None);
+    }
+}
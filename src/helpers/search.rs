@@ -0,0 +1,270 @@
+/*
+ * Shortest path searches over implicit graphs.
+ * Graphs are described by closures: `neighbours` returns the nodes (and for weighted searches the
+ * costs) reachable from a node, `is_goal` ends the search. Nodes can be any hashable value, e.g. grid
+ * positions or `(position, time)` states.
+ */
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Integer-like path costs. `Default` is used as zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    /// Nodes from the start to the goal, both included.
+    pub nodes: Vec<N>,
+}
+
+/// Nodes seen by a search, parents are stored as indices into `nodes`.
+struct Visited<N, C> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    parents: Vec<Option<usize>>,
+    costs: Vec<C>,
+}
+
+impl<N: Eq + Hash + Clone, C: Cost> Visited<N, C> {
+    fn new(start: N) -> Visited<N, C> {
+        Visited {
+            index: HashMap::from([(start.clone(), 0)]),
+            nodes: vec![start],
+            parents: vec![None],
+            costs: vec![C::default()],
+        }
+    }
+
+    /// Records `node` if it was not seen yet or if `cost` is lower. Returns its index if it was updated.
+    fn relax(&mut self, node: N, parent: usize, cost: C) -> Option<usize> {
+        match self.index.get(&node) {
+            Some(&i) if self.costs[i] <= cost => None,
+            Some(&i) => {
+                self.parents[i] = Some(parent);
+                self.costs[i] = cost;
+                Some(i)
+            }
+            None => {
+                let i = self.nodes.len();
+                self.index.insert(node.clone(), i);
+                self.nodes.push(node);
+                self.parents.push(Some(parent));
+                self.costs.push(cost);
+                Some(i)
+            }
+        }
+    }
+
+    fn path(&self, goal: usize) -> Path<N, C> {
+        let mut nodes = vec![self.nodes[goal].clone()];
+        let mut current = goal;
+        while let Some(parent) = self.parents[current] {
+            nodes.push(self.nodes[parent].clone());
+            current = parent;
+        }
+        nodes.reverse();
+        Path {
+            cost: self.costs[goal],
+            nodes,
+        }
+    }
+
+    fn distances(self) -> HashMap<N, C> {
+        self.nodes.into_iter().zip(self.costs).collect()
+    }
+}
+
+fn run_bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Visited<N, usize>, Option<usize>)
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start);
+    let mut open = VecDeque::from([0]);
+    while let Some(current) = open.pop_front() {
+        if is_goal(&visited.nodes[current]) {
+            return (visited, Some(current));
+        }
+        let cost = visited.costs[current] + 1;
+        for neighbour in neighbours(&visited.nodes[current]) {
+            if !visited.index.contains_key(&neighbour) {
+                open.extend(visited.relax(neighbour, current, cost));
+            }
+        }
+    }
+    (visited, None)
+}
+
+/// Breadth-first search, every step costs 1.
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let (visited, goal) = run_bfs(start, neighbours, is_goal);
+    goal.map(|goal| visited.path(goal))
+}
+
+/// Number of steps to every node reachable from `start`.
+pub fn bfs_distances<N, I>(start: N, neighbours: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    run_bfs(start, neighbours, |_| false).0.distances()
+}
+
+fn run_best_first<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Visited<N, C>, Option<usize>)
+where
+    N: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut visited = Visited::new(start);
+    let mut open = BinaryHeap::from([Reverse((heuristic(&visited.nodes[0]), C::default(), 0))]);
+    while let Some(Reverse((_, cost, current))) = open.pop() {
+        if cost > visited.costs[current] {
+            continue; // a cheaper way to `current` was found after this entry was pushed.
+        }
+        if is_goal(&visited.nodes[current]) {
+            return (visited, Some(current));
+        }
+        for (neighbour, step) in neighbours(&visited.nodes[current]) {
+            let cost = cost + step;
+            if let Some(i) = visited.relax(neighbour, current, cost) {
+                open.push(Reverse((cost + heuristic(&visited.nodes[i]), cost, i)));
+            }
+        }
+    }
+    (visited, None)
+}
+
+/// Dijkstra's algorithm, `neighbours` returns `(node, cost)` pairs with non-negative costs.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (visited, goal) = run_best_first(start, neighbours, |_| C::default(), is_goal);
+    goal.map(|goal| visited.path(goal))
+}
+
+/// Cost of the cheapest path to every node reachable from `start`.
+pub fn dijkstra_distances<N, C, I>(start: N, neighbours: impl FnMut(&N) -> I) -> HashMap<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    run_best_first(start, neighbours, |_| C::default(), |_| false)
+        .0
+        .distances()
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a goal.
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (visited, goal) = run_best_first(start, neighbours, heuristic, is_goal);
+    goal.map(|goal| visited.path(goal))
+}
+
+/// Cheapest path costs between all `nodes`: `result[i][j]` is the cost from `nodes[i]` to `nodes[j]`,
+/// or `None` if there is no path.
+pub fn all_pairs<N, C, I>(nodes: &[N], mut neighbours: impl FnMut(&N) -> I) -> Vec<Vec<Option<C>>>
+where
+    N: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    nodes
+        .iter()
+        .map(|from| {
+            let distances = dijkstra_distances(from.clone(), &mut neighbours);
+            nodes.iter().map(|to| distances.get(to).copied()).collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -1-> 1 -1-> 2 -1-> 3, plus a direct edge 0 -5-> 3.
+    fn weighted(node: &u32) -> Vec<(u32, u32)> {
+        match node {
+            0 => vec![(1, 1), (3, 5)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs(0, |n: &i32| [n - 1, n + 1], |n| *n == 3).unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+
+        assert_eq!(bfs(0, |_: &i32| [], |n| *n == 3), None);
+        let distances = bfs_distances(0, |n: &i32| if *n < 2 { vec![n + 1] } else { vec![] });
+        assert_eq!(distances, HashMap::from([(0, 0), (1, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(0, weighted, |n| *n == 3).unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+        assert_eq!(dijkstra_distances(0, weighted)[&3], 3);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = (3, 2);
+        let neighbours = |(x, y): &(i32, i32)| [((x + 1, *y), 1), ((*x, y + 1), 1)];
+        let heuristic = |(x, y): &(i32, i32)| (goal.0 - x).abs() + (goal.1 - y).abs();
+        let path = astar((0, 0), neighbours, heuristic, |n| *n == goal).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes.len(), 6);
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let distances = all_pairs(&[0, 2, 3], weighted);
+        assert_eq!(distances[0], vec![Some(0), Some(2), Some(3)]);
+        assert_eq!(distances[2], vec![None, None, Some(0)]);
+    }
+}
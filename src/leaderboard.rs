@@ -0,0 +1,358 @@
+/*
+ * Private leaderboard viewer.
+ * Reads the JSON export of a private leaderboard (`/<year>/leaderboard/private/view/<id>.json`),
+ * either from a file or over HTTP with the session cookie, and renders standings and per-day times.
+ * Local scores are recomputed from the star timestamps, global scores are taken from the export as
+ * they depend on the global top 100.
+ */
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    progress::{Date, AOC_UTC_OFFSET_SECONDS},
+    ANSI_BOLD, ANSI_DIM, ANSI_GOLD, ANSI_RESET, ANSI_SILVER,
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+const SESSION_FILE: &str = ".adventofcode.session";
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(PathBuf, io::Error),
+    Http(String),
+    Json(serde_json::Error),
+    MissingSession,
+}
+
+impl Display for LeaderboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeaderboardError::Io(path, e) => {
+                write!(f, "could not read \"{}\": {}", path.display(), e)
+            }
+            LeaderboardError::Http(e) => write!(f, "could not fetch leaderboard: {}", e),
+            LeaderboardError::Json(e) => write!(f, "could not parse leaderboard JSON: {}", e),
+            LeaderboardError::MissingSession => write!(
+                f,
+                "no session cookie found, set {} or create ~/{}.",
+                SESSION_VAR, SESSION_FILE
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub struct Star {
+    pub get_star_ts: i64,
+    #[serde(default)]
+    pub star_index: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub global_score: u64,
+    pub last_star_ts: i64,
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    pub fn star(&self, day: u8, part: u8) -> Option<&Star> {
+        self.completion_day_level.get(&day)?.get(&part)
+    }
+
+    pub fn stars_on(&self, day: u8) -> usize {
+        self.completion_day_level.get(&day).map_or(0, |d| d.len())
+    }
+
+    /// Seconds between getting the first and the second star of `day`.
+    pub fn part_two_delta(&self, day: u8) -> Option<i64> {
+        Some(self.star(day, 2)?.get_star_ts - self.star(day, 1)?.get_star_ts)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: BTreeMap<u64, Member>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Standing {
+    pub member: u64,
+    pub local_score: u64,
+    pub global_score: u64,
+    pub stars: u32,
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Leaderboard, LeaderboardError> {
+        serde_json::from_str(json).map_err(LeaderboardError::Json)
+    }
+
+    pub fn read(path: &Path) -> Result<Leaderboard, LeaderboardError> {
+        let json =
+            fs::read_to_string(path).map_err(|e| LeaderboardError::Io(path.to_path_buf(), e))?;
+        Leaderboard::parse(&json)
+    }
+
+    /// Fetches the leaderboard from `<base_url>/<year>/leaderboard/private/view/<id>.json`.
+    /// Please do not fetch more often than every 15 minutes.
+    pub fn fetch(
+        base_url: &str,
+        year: i32,
+        id: u64,
+        session: &str,
+    ) -> Result<Leaderboard, LeaderboardError> {
+        let url = format!(
+            "{}/{}/leaderboard/private/view/{}.json",
+            base_url.trim_end_matches('/'),
+            year,
+            id
+        );
+        let json = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(|e| LeaderboardError::Http(e.to_string()))?
+            .into_string()
+            .map_err(|e| LeaderboardError::Http(e.to_string()))?;
+        Leaderboard::parse(&json)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.event.parse().unwrap_or_else(|_| Date::today().year)
+    }
+
+    /// Unix timestamp of the moment the puzzle of `day` unlocked.
+    pub fn unlock_ts(&self, day: u8) -> i64 {
+        let date = Date {
+            year: self.year(),
+            month: 12,
+            day: u32::from(day),
+        };
+        date.days_since_epoch() * 86_400 - AOC_UTC_OFFSET_SECONDS
+    }
+
+    /// Days on which at least one member got a star.
+    pub fn days(&self) -> Vec<u8> {
+        (1..=25)
+            .filter(|day| self.members.values().any(|m| m.stars_on(*day) > 0))
+            .collect()
+    }
+
+    /// Recomputes local scores: for every star, the first member to get it scores one point per
+    /// member of the leaderboard, the second one point less and so on.
+    pub fn local_scores(&self) -> BTreeMap<u64, u64> {
+        let member_count = self.members.len() as u64;
+        let mut scores: BTreeMap<u64, u64> = self.members.keys().map(|id| (*id, 0)).collect();
+        for day in 1..=25 {
+            for part in 1..=2 {
+                let mut finishers: Vec<(Star, u64)> = self
+                    .members
+                    .values()
+                    .filter_map(|m| m.star(day, part).map(|star| (*star, m.id)))
+                    .collect();
+                finishers.sort_by_key(|(star, _)| (star.get_star_ts, star.star_index));
+                for (rank, (_, id)) in finishers.iter().enumerate() {
+                    *scores.get_mut(id).unwrap() += member_count - rank as u64;
+                }
+            }
+        }
+        scores
+    }
+
+    /// Members ordered by recomputed local score, ties go to whoever got their last star first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let scores = self.local_scores();
+        let mut standings: Vec<&Member> = self.members.values().collect();
+        standings.sort_by_key(|m| (std::cmp::Reverse(scores[&m.id]), m.last_star_ts, m.id));
+        standings
+            .into_iter()
+            .map(|m| Standing {
+                member: m.id,
+                local_score: scores[&m.id],
+                global_score: m.global_score,
+                stars: m.stars,
+            })
+            .collect()
+    }
+
+    pub fn render_standings(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "{}Private leaderboard {}{} · {} members",
+                ANSI_BOLD,
+                self.event,
+                ANSI_RESET,
+                self.members.len()
+            ),
+            String::new(),
+            format!("{:>30}1111111111222222", ""),
+            format!("{:>21}1234567890123456789012345", ""),
+        ];
+        for (rank, standing) in self.standings().iter().enumerate() {
+            let member = &self.members[&standing.member];
+            let calendar: String = (1..=25)
+                .map(|day| match member.stars_on(day) {
+                    2 => format!("{}*{}", ANSI_GOLD, ANSI_RESET),
+                    1 => format!("{}*{}", ANSI_SILVER, ANSI_RESET),
+                    _ => format!("{}.{}", ANSI_DIM, ANSI_RESET),
+                })
+                .collect();
+            let mut line = format!(
+                "{:>3}) {:>5} {:>6} {:>2} {}  {}",
+                rank + 1,
+                standing.local_score,
+                standing.global_score,
+                standing.stars,
+                calendar,
+                member.display_name()
+            );
+            if standing.local_score != member.local_score {
+                line += &format!(
+                    " {}(export: {}){}",
+                    ANSI_DIM, member.local_score, ANSI_RESET
+                );
+            }
+            lines.push(line);
+        }
+        lines.push(String::new());
+        lines.push("columns: rank, local score, global score, stars".to_string());
+        lines.join("\n")
+    }
+
+    /// Lists the time each member needed for both parts of `day`, counted from the puzzle unlock.
+    pub fn render_day(&self, day: u8) -> String {
+        let unlock = self.unlock_ts(day);
+        let time = |star: Option<&Star>| {
+            star.map_or("-".to_string(), |s| format_duration(s.get_star_ts - unlock))
+        };
+
+        let mut members: Vec<&Member> = self
+            .members
+            .values()
+            .filter(|m| m.stars_on(day) > 0)
+            .collect();
+        members.sort_by_key(|m| {
+            let last = m.star(day, 2).or_else(|| m.star(day, 1)).unwrap();
+            (std::cmp::Reverse(m.stars_on(day)), last.get_star_ts, m.id)
+        });
+
+        let mut lines = vec![
+            format!("{}Day {}{}", ANSI_BOLD, day, ANSI_RESET),
+            format!("{:>10} {:>10} {:>10}", "Part 1", "Part 2", "Delta"),
+        ];
+        for member in members {
+            lines.push(format!(
+                "{:>10} {:>10} {:>10}  {}",
+                time(member.star(day, 1)),
+                time(member.star(day, 2)),
+                member
+                    .part_two_delta(day)
+                    .map_or("-".to_string(), format_duration),
+                member.display_name()
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Formats seconds as `HH:MM:SS`, hours are not wrapped at 24.
+pub fn format_duration(seconds: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Reads the session cookie from `AOC_SESSION` or `~/.adventofcode.session`, the file aoc-cli uses.
+pub fn session() -> Result<String, LeaderboardError> {
+    if let Ok(session) = env::var(SESSION_VAR) {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+    let home = env::var("HOME").map_err(|_| LeaderboardError::MissingSession)?;
+    fs::read_to_string(PathBuf::from(home).join(SESSION_FILE))
+        .map(|session| session.trim().to_string())
+        .map_err(|_| LeaderboardError::MissingSession)
+}
+
+pub fn base_url() -> String {
+    env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Leaderboard {
+        Leaderboard::parse(include_str!("fixtures/leaderboard.json")).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let leaderboard = fixture();
+        assert_eq!(leaderboard.year(), 2022);
+        assert_eq!(leaderboard.members.len(), 3);
+        assert_eq!(
+            leaderboard.members[&103].display_name(),
+            "(anonymous user #103)"
+        );
+        assert_eq!(leaderboard.days(), vec![1, 2, 3]);
+        assert!(Leaderboard::parse("{\"event\": \"2022\"}").is_err());
+    }
+
+    #[test]
+    fn test_local_scores() {
+        let leaderboard = fixture();
+        let scores = leaderboard.local_scores();
+        for member in leaderboard.members.values() {
+            assert_eq!(scores[&member.id], member.local_score);
+        }
+        let order: Vec<u64> = leaderboard.standings().iter().map(|s| s.member).collect();
+        assert_eq!(order, vec![101, 103, 102]);
+    }
+
+    #[test]
+    fn test_day_times() {
+        let leaderboard = fixture();
+        assert_eq!(leaderboard.unlock_ts(1), 1669870800);
+        assert_eq!(leaderboard.members[&101].part_two_delta(3), Some(89600));
+        assert_eq!(leaderboard.members[&102].part_two_delta(2), None);
+
+        let day = leaderboard.render_day(3);
+        assert!(day.contains("00:06:40   25:00:00   24:53:20  alice"));
+        assert!(day.contains("00:05:00          -          -  (anonymous user #103)"));
+        assert!(!day.contains("bob"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(3725), "01:02:05");
+        assert_eq!(format_duration(90000), "25:00:00");
+    }
+}
@@ -6,11 +6,23 @@
 use std::env;
 use std::fs;
 
+pub mod encryption;
+pub mod examples;
+pub mod explain;
+pub mod fingerprint;
 pub mod helpers;
+pub mod leaderboard;
+pub mod log;
+pub mod params;
+pub mod progress;
+pub mod recorder;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_GOLD: &str = "\x1b[93m";
+pub const ANSI_SILVER: &str = "\x1b[37m";
+pub const ANSI_DIM: &str = "\x1b[2m";
 
 #[macro_export]
 macro_rules! solve {
@@ -19,16 +31,29 @@ macro_rules! solve {
         use std::fmt::Display;
         use std::time::Instant;
 
-        fn print_result<T: Display>(func: impl FnOnce(&str) -> Option<T>, input: &str) {
-            let timer = Instant::now();
-            let result = func(input);
-            let elapsed = timer.elapsed();
+        fn print_result<T: Display>(func: impl FnOnce(&str) -> Option<T>, input: &str, part: u8) {
+            // log lines are held back while the solution runs, and the time spent formatting
+            // them is subtracted, so that logging is not timed.
+            let ((result, elapsed), log) = advent_of_code::log::buffered(|| {
+                let timer = Instant::now();
+                let result = func(input);
+                (result, timer.elapsed())
+            });
+            let elapsed = elapsed.saturating_sub(log.spent);
+            eprint!("{}", log.output);
             match result {
                 Some(result) => {
                     println!(
                         "{} {}(elapsed: {:.2?}){}",
                         result, ANSI_ITALIC, elapsed, ANSI_RESET
                     );
+                    if let Ok(day) = env!("CARGO_BIN_NAME").parse() {
+                        advent_of_code::progress::print_verification(
+                            day,
+                            part,
+                            &result.to_string(),
+                        );
+                    }
                 }
                 None => {
                     println!("not solved.")
@@ -37,7 +62,10 @@ macro_rules! solve {
         }
 
         println!("🎄 {}Part {}{} 🎄", ANSI_BOLD, $part, ANSI_RESET);
-        print_result($solver, $input);
+        print_result($solver, $input, $part);
+        if $part == 2 {
+            advent_of_code::params::warn_unused();
+        }
     }};
 }
 
@@ -46,8 +74,32 @@ pub fn read_file(folder: &str, day: u8) -> String {
 
     let filepath = cwd.join("src").join(folder).join(format!("{:02}.txt", day));
 
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    // an empty plaintext file is a placeholder, e.g. left by `cargo scaffold`, so prefer the encrypted input.
+    let f = fs::read_to_string(&filepath);
+    let contents = match f {
+        Ok(contents) if !contents.trim().is_empty() => contents,
+        _ if encryption::encrypted_path(&filepath).exists() => encryption::Cipher::from_env()
+            .and_then(|cipher| cipher.decrypt_file(&filepath))
+            .unwrap_or_else(|e| panic!("{}", e)),
+        Ok(contents) => contents,
+        Err(e) => panic!("could not open input file: {}", e),
+    };
+    check_contents(folder, day, &contents);
+    contents
+}
+
+/// Reports empty files. Days check the shape of their input with `fingerprint::check`.
+fn check_contents(folder: &str, day: u8, contents: &str) {
+    if contents.trim().is_empty() {
+        if folder == "inputs" {
+            panic!(
+                "input for day {} not downloaded, run `cargo download {}` or paste it into src/inputs/{:02}.txt.",
+                day, day, day
+            );
+        } else {
+            panic!("src/{}/{:02}.txt is empty.", folder, day);
+        }
+    }
 }
 
 fn parse_time(val: &str, postfix: &str) -> f64 {
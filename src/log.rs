@@ -0,0 +1,187 @@
+/*
+ * Leveled debug logging for solutions.
+ * Log lines go to stderr so they never mix with answers. The level is read from `-v` flags
+ * (`-v` info, `-vv` debug, `-vvv` trace) or the `AOC_LOG` environment variable.
+ * Example: `advent_of_code::debug!("cycle {}: x={}", cycle, x);`.
+ */
+use std::{
+    cell::RefCell,
+    env,
+    fmt::{Arguments, Display},
+    sync::atomic::{AtomicU8, Ordering},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off = 0,
+    Info = 1,
+    Debug = 2,
+    Trace = 3,
+}
+
+impl Level {
+    fn from_u8(value: u8) -> Level {
+        match value {
+            0 => Level::Off,
+            1 => Level::Info,
+            2 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    fn parse(value: &str) -> Option<Level> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "0" => Some(Level::Off),
+            "info" | "1" => Some(Level::Info),
+            "debug" | "2" => Some(Level::Debug),
+            "trace" | "3" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Off => write!(f, "off"),
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace"),
+        }
+    }
+}
+
+const UNINITIALIZED: u8 = u8::MAX;
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(UNINITIALIZED);
+
+/// Log output held back by `buffered`, with the time spent formatting it.
+#[derive(Debug, Default)]
+pub struct Held {
+    pub output: String,
+    pub spent: Duration,
+}
+
+thread_local! {
+    static BUFFER: RefCell<Option<Held>> = const { RefCell::new(None) };
+}
+
+/// Returns whether messages of `level` are printed. This is the only work done by the log macros
+/// when a level is disabled: message arguments are not evaluated.
+#[inline(always)]
+pub fn enabled(level: Level) -> bool {
+    let max_level = MAX_LEVEL.load(Ordering::Relaxed);
+    if max_level == UNINITIALIZED {
+        return level <= init();
+    }
+    level as u8 <= max_level
+}
+
+/// Reads the log level from the command line and the environment. `-v` flags take precedence
+/// over `AOC_LOG`. Called automatically on first use.
+pub fn init() -> Level {
+    let verbosity = env::args()
+        .skip(1)
+        .filter(|arg| arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v'))
+        .map(|arg| arg.len() - 1)
+        .sum::<usize>();
+
+    let level = if verbosity > 0 {
+        Level::from_u8(verbosity.min(Level::Trace as usize) as u8)
+    } else {
+        env::var("AOC_LOG")
+            .ok()
+            .and_then(|value| Level::parse(&value))
+            .unwrap_or(Level::Off)
+    };
+    set_level(level);
+    level
+}
+
+pub fn set_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+pub fn write(level: Level, args: Arguments) {
+    let start = Instant::now();
+    let line = format!("[{}] {}", level, args);
+    let buffered = BUFFER.with(|buffer| match buffer.borrow_mut().as_mut() {
+        Some(held) => {
+            held.output.push_str(&line);
+            held.output.push('\n');
+            held.spent += start.elapsed();
+            true
+        }
+        None => false,
+    });
+    if !buffered {
+        eprintln!("{}", line);
+    }
+}
+
+/// Runs `f` while holding back log output, then returns its result and the held back output.
+/// `solve!` uses this and subtracts `Held::spent` so formatting and writing log lines does not
+/// count towards a solution's timing. The expressions passed to an enabled log macro are still
+/// evaluated by the solution and stay timed.
+pub fn buffered<T>(f: impl FnOnce() -> T) -> (T, Held) {
+    let previous = BUFFER.with(|buffer| buffer.replace(Some(Held::default())));
+    let result = f();
+    let held = BUFFER.with(|buffer| buffer.replace(previous));
+    (result, held.unwrap_or_default())
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {{
+        if $crate::log::enabled($crate::log::Level::Info) {
+            $crate::log::write($crate::log::Level::Info, format_args!($($arg)*));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::write($crate::log::Level::Debug, format_args!($($arg)*));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {{
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            $crate::log::write($crate::log::Level::Trace, format_args!($($arg)*));
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(Level::parse("DEBUG"), Some(Level::Debug));
+        assert_eq!(Level::parse("3"), Some(Level::Trace));
+        assert_eq!(Level::parse("loud"), None);
+    }
+
+    #[test]
+    fn test_buffered() {
+        set_level(Level::Debug);
+        let (result, held) = buffered(|| {
+            write(Level::Info, format_args!("a {}", 1));
+            write(Level::Debug, format_args!("b"));
+            42
+        });
+        assert_eq!(result, 42);
+        assert_eq!(held.output, "[info] a 1\n[debug] b\n");
+        assert!(held.spent > Duration::ZERO);
+        assert!(enabled(Level::Debug));
+        assert!(!enabled(Level::Trace));
+    }
+}
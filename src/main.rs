@@ -3,23 +3,33 @@
  * There is no need to edit this file unless you want to change template functionality.
  */
 use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
-use std::process::Command;
+use std::process::{self, Command, Output};
 
-fn main() {
+fn run_day(day: &str, extra_args: &[&str]) -> Output {
+    let mut args = vec!["run", "--bin", day];
+    if cfg!(not(debug_assertions)) {
+        args.push("--release");
+    }
+    if !extra_args.is_empty() {
+        args.push("--");
+        args.extend(extra_args);
+    }
+    Command::new("cargo").args(&args).output().unwrap()
+}
+
+fn print_header(day: &str) {
+    println!("----------");
+    println!("{}| Day {} |{}", ANSI_BOLD, day, ANSI_RESET);
+    println!("----------");
+}
+
+fn solve_all() {
     let total: f64 = (1..=25)
         .map(|day| {
             let day = format!("{:02}", day);
+            let cmd = run_day(&day, &[]);
 
-            let mut args = vec!["run", "--bin", &day];
-            if cfg!(not(debug_assertions)) {
-                args.push("--release");
-            }
-
-            let cmd = Command::new("cargo").args(&args).output().unwrap();
-
-            println!("----------");
-            println!("{}| Day {} |{}", ANSI_BOLD, day, ANSI_RESET);
-            println!("----------");
+            print_header(&day);
 
             let output = String::from_utf8(cmd.stdout).unwrap();
             let is_empty = output.is_empty();
@@ -46,3 +56,46 @@ fn main() {
         ANSI_BOLD, ANSI_RESET, ANSI_ITALIC, total, ANSI_RESET
     );
 }
+
+fn check_all_examples() {
+    let failed_days: Vec<String> = (1..=25)
+        .filter_map(|day| {
+            let day = format!("{:02}", day);
+            let cmd = run_day(&day, &["--examples"]);
+            let output = String::from_utf8(cmd.stdout).unwrap();
+            if output.is_empty() {
+                return None;
+            }
+
+            print_header(&day);
+            println!("{}", output.trim());
+
+            if cmd.status.success() {
+                None
+            } else {
+                Some(day)
+            }
+        })
+        .collect();
+
+    if failed_days.is_empty() {
+        println!("{}All examples passed.{}", ANSI_BOLD, ANSI_RESET);
+    } else {
+        println!(
+            "{}Examples failed for days:{} {}",
+            ANSI_BOLD,
+            ANSI_RESET,
+            failed_days.join(", ")
+        );
+        process::exit(1);
+    }
+}
+
+fn main() {
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains("--examples") {
+        check_all_examples();
+    } else {
+        solve_all();
+    }
+}
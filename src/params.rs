@@ -0,0 +1,209 @@
+/*
+ * Named puzzle parameters.
+ * Some puzzles use different constants for the example and the real input, e.g. the row that is
+ * checked on day 15. A solution declares them with their value for the real input:
+ *
+ *     const TARGET_Y: Param<i32> = Param::new("target_y", 2_000_000);
+ *
+ * and reads them with `TARGET_Y.get()`, so solvers keep their `fn(&str) -> Option<T>` signature.
+ * Examples override parameters in their answers file (`target_y: 10`), runs override them on the
+ * command line (`--param target_y=10`). `solve!` warns about `--param` names that no parameter read,
+ * so a typo does not silently run with the default.
+ */
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+
+thread_local! {
+    static OVERRIDES: RefCell<Vec<BTreeMap<String, String>>> = const { RefCell::new(Vec::new()) };
+}
+
+static ARGS: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+/// Names of `--param` flags that a parameter has read.
+static USED_ARGS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// Parses `name=value` assignments.
+fn parse_assignments(values: &[String]) -> Result<BTreeMap<String, String>, String> {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| format!("expected `--param name=value`, got \"{}\"", value))
+        })
+        .collect()
+}
+
+/// Parameters passed with `--param name=value`, read once on first use.
+fn from_args() -> &'static BTreeMap<String, String> {
+    ARGS.get_or_init(|| {
+        let values: Vec<String> = pico_args::Arguments::from_env()
+            .values_from_str("--param")
+            .unwrap_or_default();
+        parse_assignments(&values).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            BTreeMap::new()
+        })
+    })
+}
+
+/// Runs `f` with `values` overriding parameters on the current thread, e.g. the values of an
+/// example's answers file. Overrides take precedence over `--param` flags.
+pub fn with_overrides<K, V, R>(values: impl IntoIterator<Item = (K, V)>, f: impl FnOnce() -> R) -> R
+where
+    K: ToString,
+    V: ToString,
+{
+    let values = values
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    OVERRIDES.with(|overrides| overrides.borrow_mut().push(values));
+    let _frame = Frame;
+    f()
+}
+
+/// Pops the innermost override frame when dropped, also when the solver panics.
+struct Frame;
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        OVERRIDES.with(|overrides| overrides.borrow_mut().pop());
+    }
+}
+
+fn lookup(name: &'static str) -> Option<String> {
+    OVERRIDES
+        .with(|overrides| {
+            overrides
+                .borrow()
+                .iter()
+                .rev()
+                .find_map(|values| values.get(name).cloned())
+        })
+        .or_else(|| {
+            let value = from_args().get(name).cloned();
+            if value.is_some() {
+                USED_ARGS.lock().unwrap().insert(name);
+            }
+            value
+        })
+}
+
+fn unused_names<'a>(args: &'a BTreeMap<String, String>, used: &BTreeSet<&str>) -> Vec<&'a str> {
+    args.keys()
+        .map(|name| name.as_str())
+        .filter(|name| !used.contains(name))
+        .collect()
+}
+
+/// Warns about every `--param` that no parameter has read so far, e.g. because its name is
+/// misspelled. Called by `solve!` once both parts ran.
+pub fn warn_unused() {
+    let used = USED_ARGS.lock().unwrap();
+    for name in unused_names(from_args(), &used) {
+        eprintln!(
+            "warning: --param {} is not a parameter of this solution, it was ignored.",
+            name
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    name: &'static str,
+    default: T,
+}
+
+impl<T> Param<T>
+where
+    T: FromStr + Clone,
+    T::Err: Display,
+{
+    pub const fn new(name: &'static str, default: T) -> Param<T> {
+        Param { name, default }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The overridden value if there is one, the default otherwise. Panics if an override can't be
+    /// parsed, as the answer would be meaningless.
+    pub fn get(&self) -> T {
+        match lookup(self.name) {
+            Some(value) => value
+                .parse()
+                .unwrap_or_else(|e| panic!("invalid value \"{}\" for {}: {}", value, self.name, e)),
+            None => self.default.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUNDS: Param<u32> = Param::new("rounds", 20);
+
+    #[test]
+    fn test_overrides() {
+        assert_eq!(ROUNDS.get(), 20);
+        let rounds = with_overrides([("rounds", "3")], || {
+            (
+                ROUNDS.get(),
+                with_overrides([("rounds", 4)], || ROUNDS.get()),
+            )
+        });
+        assert_eq!(rounds, (3, 4));
+        assert_eq!(with_overrides([("other", "1")], || ROUNDS.get()), 20);
+        assert_eq!(ROUNDS.get(), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid value \"many\" for rounds")]
+    fn test_invalid_override() {
+        with_overrides([("rounds", "many")], || ROUNDS.get());
+    }
+
+    #[test]
+    fn test_overrides_after_panic() {
+        let result = std::panic::catch_unwind(|| {
+            with_overrides([("rounds", "3")], || panic!("solver failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(ROUNDS.get(), 20);
+    }
+
+    #[test]
+    fn test_unused_names() {
+        let args = BTreeMap::from([
+            ("search_max".to_string(), "20".to_string()),
+            ("serch_max".to_string(), "20".to_string()),
+        ]);
+        assert_eq!(
+            unused_names(&args, &BTreeSet::from(["search_max"])),
+            vec!["serch_max"]
+        );
+        assert!(unused_names(&args, &BTreeSet::from(["search_max", "serch_max"])).is_empty());
+    }
+
+    #[test]
+    fn test_parse_assignments() {
+        let values = parse_assignments(&["target_y=10".to_string(), "minutes = 26".to_string()]);
+        assert_eq!(
+            values,
+            Ok(BTreeMap::from([
+                ("minutes".to_string(), "26".to_string()),
+                ("target_y".to_string(), "10".to_string())
+            ]))
+        );
+        assert!(parse_assignments(&["target_y".to_string()]).is_err());
+    }
+}
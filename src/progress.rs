@@ -0,0 +1,348 @@
+/*
+ * Local star tracking.
+ * Declare the accepted answers for your input in `src/inputs/NN.answers` (same format as the example
+ * answers). Whenever `solve!` computes a declared answer, the star is recorded in `src/progress.txt`
+ * and shows up in the calendar drawn by `cargo stars`. Answers files are git-ignored like the inputs,
+ * `cargo encrypt` writes `NN.answers.enc` copies that are decrypted when the plaintext is missing.
+ */
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{encryption, examples, ANSI_BOLD, ANSI_DIM, ANSI_GOLD, ANSI_RESET, ANSI_SILVER};
+
+/// Puzzles unlock at midnight EST (UTC-5), solve dates use the same time zone.
+pub const AOC_UTC_OFFSET_SECONDS: i64 = -5 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Converts days since 1970-01-01 to a calendar date.
+    /// see: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Inverse of `from_days`.
+    /// see: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    pub fn days_since_epoch(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Date::from_days((seconds + AOC_UTC_OFFSET_SECONDS).div_euclid(86_400))
+    }
+
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.split('-');
+        let date = Date {
+            year: parts.next()?.parse().ok()?,
+            month: parts.next()?.parse().ok()?,
+            day: parts.next()?.parse().ok()?,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(date)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    stars: BTreeMap<(u8, u8), Date>,
+}
+
+fn progress_path() -> PathBuf {
+    env::current_dir().unwrap().join("src").join("progress.txt")
+}
+
+impl Progress {
+    /// Parses lines of `<day> <part> <date>`, e.g. `01 2 2022-12-01`.
+    pub fn parse(text: &str) -> Result<Progress, String> {
+        let mut stars = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let star = (|| {
+                let day = parts.next()?.parse::<u8>().ok()?;
+                let part = parts.next()?.parse::<u8>().ok()?;
+                let date = Date::parse(parts.next()?)?;
+                Some(((day, part), date))
+            })();
+            match star {
+                Some((key, date)) => {
+                    stars.insert(key, date);
+                }
+                None => {
+                    return Err(format!(
+                        "progress.txt:{}: expected `<day> <part> <date>`",
+                        i + 1
+                    ))
+                }
+            }
+        }
+        Ok(Progress { stars })
+    }
+
+    pub fn load() -> Progress {
+        match fs::read_to_string(progress_path()) {
+            Ok(text) => Progress::parse(&text).unwrap_or_else(|e| panic!("{}", e)),
+            Err(_) => Progress::default(),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        fs::write(progress_path(), self.to_string())
+    }
+
+    /// Records a star. Returns `false` if it was recorded before.
+    pub fn record(&mut self, day: u8, part: u8, date: Date) -> bool {
+        if self.stars.contains_key(&(day, part)) {
+            return false;
+        }
+        self.stars.insert((day, part), date);
+        true
+    }
+
+    pub fn stars(&self, day: u8) -> usize {
+        (1..=2)
+            .filter(|part| self.stars.contains_key(&(day, *part)))
+            .count()
+    }
+
+    pub fn total_stars(&self) -> usize {
+        self.stars.len()
+    }
+
+    /// Date of the last star earned for `day`.
+    pub fn solved_on(&self, day: u8) -> Option<Date> {
+        (1..=2)
+            .filter_map(|part| self.stars.get(&(day, part)))
+            .max()
+            .copied()
+    }
+
+    fn gold_streaks(&self) -> Vec<usize> {
+        let mut streaks = vec![0];
+        for day in 1..=25 {
+            if self.stars(day) == 2 {
+                *streaks.last_mut().unwrap() += 1;
+            } else {
+                streaks.push(0);
+            }
+        }
+        streaks
+    }
+
+    /// Longest run of consecutive days with both stars.
+    pub fn longest_streak(&self) -> usize {
+        self.gold_streaks().into_iter().max().unwrap_or(0)
+    }
+
+    /// Run of consecutive days with both stars that ends at the last day with any star.
+    pub fn current_streak(&self) -> usize {
+        let last_day = match self.stars.keys().map(|(day, _)| *day).max() {
+            Some(day) => day,
+            None => return 0,
+        };
+        (1..=last_day)
+            .rev()
+            .take_while(|day| self.stars(*day) == 2)
+            .count()
+    }
+
+    /// Draws the 25 days as a calendar of 5 weeks with gold (both parts) and silver (one part) stars.
+    pub fn calendar(&self) -> String {
+        let mut lines = Vec::new();
+        for week in 0..5 {
+            let cells: Vec<String> = (1..=5)
+                .map(|weekday| {
+                    let day = week * 5 + weekday;
+                    let stars = match self.stars(day) {
+                        2 => format!("{}**{}", ANSI_GOLD, ANSI_RESET),
+                        1 => format!("{}* {}", ANSI_SILVER, ANSI_RESET),
+                        _ => format!("{}..{}", ANSI_DIM, ANSI_RESET),
+                    };
+                    let date = self.solved_on(day).map_or("     ".to_string(), |d| {
+                        format!("{:02}-{:02}", d.month, d.day)
+                    });
+                    format!("{}{:>2}{} {} {}", ANSI_BOLD, day, ANSI_RESET, stars, date)
+                })
+                .collect();
+            lines.push(cells.join("   "));
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "Stars: {}/50 · longest streak: {} days · current streak: {} days",
+            self.total_stars(),
+            self.longest_streak(),
+            self.current_streak()
+        ));
+        lines.join("\n")
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# <day> <part> <date solved>")?;
+        for ((day, part), date) in &self.stars {
+            writeln!(f, "{:02} {} {}", day, part, date)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    Correct { new_star: bool },
+    Incorrect { expected: String },
+    Unknown,
+}
+
+/// Reads an answers file, or decrypts its `.enc` copy if only that exists.
+fn read_answers(path: &Path) -> Option<String> {
+    if let Ok(text) = fs::read_to_string(path) {
+        return Some(text);
+    }
+    if !encryption::encrypted_path(path).exists() {
+        return None;
+    }
+    encryption::Cipher::from_env()
+        .and_then(|cipher| cipher.decrypt_file(path))
+        .map_err(|e| eprintln!("could not read the accepted answers: {}", e))
+        .ok()
+}
+
+/// Compares `answer` to the answer declared in `src/inputs/NN.answers` and records a star
+/// in the progress file if it matches.
+pub fn verify(day: u8, part: u8, answer: &str) -> Verification {
+    let file_name = format!("{:02}.answers", day);
+    let path = env::current_dir()
+        .unwrap()
+        .join("src")
+        .join("inputs")
+        .join(&file_name);
+    let expected = read_answers(&path).and_then(|text| {
+        examples::parse_values(&file_name, &text)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .remove(if part == 1 { "part_one" } else { "part_two" })
+            .filter(|expected| !expected.is_empty())
+    });
+
+    match expected {
+        None => Verification::Unknown,
+        Some(expected) if expected != answer => Verification::Incorrect { expected },
+        Some(_) => {
+            let mut progress = Progress::load();
+            let new_star = progress.record(day, part, Date::today());
+            if new_star {
+                if let Err(e) = progress.save() {
+                    eprintln!("could not save progress: {}", e);
+                }
+            }
+            Verification::Correct { new_star }
+        }
+    }
+}
+
+/// Called by `solve!` after a part printed its answer. Stays silent if no answer is declared.
+pub fn print_verification(day: u8, part: u8, answer: &str) {
+    match verify(day, part, answer) {
+        Verification::Correct { new_star: true } => {
+            println!("{}⭐ correct, star recorded.{}", ANSI_GOLD, ANSI_RESET)
+        }
+        Verification::Correct { new_star: false } => println!("⭐ correct."),
+        Verification::Incorrect { expected } => {
+            println!("✗ incorrect, the accepted answer is {}.", expected)
+        }
+        Verification::Unknown => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        Date::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!(Date::from_days(0), date("1970-01-01"));
+        assert_eq!(Date::from_days(19327), date("2022-12-01"));
+        assert_eq!(Date::from_days(19417), date("2023-03-01"));
+        for days in [0, 19327, 19417, -1, 20000] {
+            assert_eq!(Date::from_days(days).days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        let text = "# <day> <part> <date solved>\n01 1 2022-12-01\n01 2 2022-12-02\n";
+        let progress = Progress::parse(text).unwrap();
+        assert_eq!(progress.stars(1), 2);
+        assert_eq!(progress.solved_on(1), Some(date("2022-12-02")));
+        assert_eq!(progress.to_string(), text);
+        assert!(Progress::parse("01 one 2022-12-01").is_err());
+    }
+
+    #[test]
+    fn test_streaks() {
+        let mut progress = Progress::default();
+        for day in [1, 2, 3, 5, 6] {
+            progress.record(day, 1, date("2022-12-01"));
+            progress.record(day, 2, date("2022-12-01"));
+        }
+        progress.record(7, 1, date("2022-12-07"));
+        assert!(!progress.record(7, 1, date("2022-12-08")));
+        assert_eq!(progress.total_stars(), 11);
+        assert_eq!(progress.longest_streak(), 3);
+        assert_eq!(progress.current_streak(), 0);
+
+        progress.record(7, 2, date("2022-12-07"));
+        assert_eq!(progress.current_streak(), 3);
+    }
+}
@@ -0,0 +1,400 @@
+/*
+ * Frame recording for simulations.
+ * Push frames into a `Recorder` while a simulation runs, then export them as an animated GIF,
+ * an asciinema cast file, or replay them in the terminal.
+ */
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+/// Size in pixels of one character cell in GIF exports.
+const CELL_SIZE: usize = 4;
+const DEFAULT_FPS: u32 = 30;
+
+/// Colors used for common map characters in GIF exports. Other characters are drawn white.
+const PALETTE: [(char, [u8; 3]); 8] = [
+    (' ', [0x0f, 0x0f, 0x23]),
+    ('.', [0x0f, 0x0f, 0x23]),
+    ('#', [0x8c, 0x8c, 0x8c]),
+    ('o', [0xff, 0xd7, 0x00]),
+    ('~', [0x00, 0x99, 0xff]),
+    ('H', [0xff, 0x33, 0x33]),
+    ('T', [0x00, 0xcc, 0x00]),
+    ('s', [0x99, 0x99, 0xcc]),
+];
+const FALLBACK_COLOR: [u8; 3] = [0xff, 0xff, 0xff];
+
+/// A single snapshot of a simulation, stored as rows of characters.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Frame {
+    rows: Vec<String>,
+}
+
+impl Frame {
+    pub fn new(rows: Vec<String>) -> Self {
+        Frame { rows }
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+impl From<String> for Frame {
+    fn from(text: String) -> Self {
+        Frame::new(text.lines().map(|line| line.to_string()).collect())
+    }
+}
+
+impl From<&str> for Frame {
+    fn from(text: &str) -> Self {
+        Frame::from(text.to_string())
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum RecorderError {
+    Io(io::Error),
+    Gif(gif::EncodingError),
+    FrameTooLarge(usize, usize),
+    UnsupportedFormat(PathBuf),
+    InvalidArgument(pico_args::Error),
+}
+
+impl Display for RecorderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecorderError::Io(e) => write!(f, "could not write recording: {}", e),
+            RecorderError::Gif(e) => write!(f, "could not encode gif: {}", e),
+            RecorderError::FrameTooLarge(width, height) => {
+                write!(
+                    f,
+                    "frames of {}x{} cells are too large for a gif.",
+                    width, height
+                )
+            }
+            RecorderError::UnsupportedFormat(path) => write!(
+                f,
+                "unsupported recording format \"{}\", expected a .gif or .cast file.",
+                path.display()
+            ),
+            RecorderError::InvalidArgument(e) => write!(f, "invalid recording option: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for RecorderError {
+    fn from(e: io::Error) -> Self {
+        RecorderError::Io(e)
+    }
+}
+
+impl From<gif::EncodingError> for RecorderError {
+    fn from(e: gif::EncodingError) -> Self {
+        RecorderError::Gif(e)
+    }
+}
+
+/// Collects frames pushed by a simulation.
+///
+/// A disabled recorder never calls the closure passed to `record`, so frames are only rendered
+/// when somebody is going to look at them.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    frames: Option<Vec<Frame>>,
+}
+
+impl Recorder {
+    pub const fn disabled() -> Self {
+        Recorder { frames: None }
+    }
+
+    pub fn enabled() -> Self {
+        Recorder {
+            frames: Some(Vec::new()),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_enabled(&self) -> bool {
+        self.frames.is_some()
+    }
+
+    #[inline(always)]
+    pub fn record<F: FnOnce() -> Frame>(&mut self, frame: F) {
+        if let Some(frames) = &mut self.frames {
+            frames.push(frame());
+        }
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        self.frames.as_deref().unwrap_or(&[])
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        self.frames().iter().fold((0, 0), |(width, height), frame| {
+            (width.max(frame.width()), height.max(frame.height()))
+        })
+    }
+
+    /// Writes all frames as an animated GIF. Frames of different sizes are drawn top-left aligned
+    /// on a canvas large enough for the biggest frame.
+    pub fn write_gif(&self, path: &Path, fps: u32) -> Result<(), RecorderError> {
+        let (width, height) = self.dimensions();
+        let pixel_width = u16::try_from(width * CELL_SIZE)
+            .map_err(|_| RecorderError::FrameTooLarge(width, height))?;
+        let pixel_height = u16::try_from(height * CELL_SIZE)
+            .map_err(|_| RecorderError::FrameTooLarge(width, height))?;
+
+        let mut palette: Vec<u8> = PALETTE.iter().flat_map(|(_, rgb)| *rgb).collect();
+        palette.extend(FALLBACK_COLOR);
+        let fallback_index = PALETTE.len() as u8;
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, pixel_width, pixel_height, &palette)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        let delay = (100 / fps.max(1)).max(1) as u16;
+        for frame in self.frames() {
+            let mut pixels = vec![0u8; pixel_width as usize * pixel_height as usize];
+            for (y, row) in frame.rows().iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    let index = PALETTE
+                        .iter()
+                        .position(|(p, _)| *p == c)
+                        .map_or(fallback_index, |i| i as u8);
+                    for dy in 0..CELL_SIZE {
+                        let start = (y * CELL_SIZE + dy) * pixel_width as usize + x * CELL_SIZE;
+                        pixels[start..start + CELL_SIZE].fill(index);
+                    }
+                }
+            }
+            let mut gif_frame =
+                gif::Frame::from_indexed_pixels(pixel_width, pixel_height, pixels, None);
+            gif_frame.delay = delay;
+            encoder.write_frame(&gif_frame)?;
+        }
+        Ok(())
+    }
+
+    /// Writes all frames as an asciinema v2 cast, see: https://docs.asciinema.org/manual/asciicast/v2/
+    pub fn write_cast<W: Write>(&self, mut writer: W, fps: u32) -> io::Result<()> {
+        let (width, height) = self.dimensions();
+        writeln!(
+            writer,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+            width,
+            height + 1
+        )?;
+        let frame_time = 1.0 / fps.max(1) as f64;
+        for (i, frame) in self.frames().iter().enumerate() {
+            let data = format!("{}{}", CLEAR_SCREEN, frame).replace('\n', "\r\n");
+            writeln!(
+                writer,
+                "[{:.6}, \"o\", \"{}\"]",
+                i as f64 * frame_time,
+                escape_json(&data)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draws every frame to the terminal, waiting `1 / fps` seconds between frames.
+    pub fn replay(&self, fps: u32) {
+        let frame_time = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+        for frame in self.frames() {
+            print!("{}{}", CLEAR_SCREEN, frame);
+            io::stdout().flush().ok();
+            thread::sleep(frame_time);
+        }
+    }
+}
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Where recorded frames should go, as requested on the command line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Output {
+    Gif(PathBuf, u32),
+    Cast(PathBuf, u32),
+    Replay(u32),
+}
+
+impl Output {
+    /// Reads `--record <file.gif|file.cast>` or `--replay` and an optional `--fps <n>`
+    /// from the command line. Returns `None` if recording was not requested.
+    pub fn from_args() -> Result<Option<Output>, RecorderError> {
+        Output::parse(pico_args::Arguments::from_env())
+    }
+
+    fn parse(mut args: pico_args::Arguments) -> Result<Option<Output>, RecorderError> {
+        let fps = args
+            .opt_value_from_str("--fps")
+            .map_err(RecorderError::InvalidArgument)?
+            .unwrap_or(DEFAULT_FPS);
+        let replay = args.contains("--replay");
+        let path: Option<PathBuf> = args
+            .opt_value_from_str("--record")
+            .map_err(RecorderError::InvalidArgument)?;
+
+        match path {
+            Some(path) => Output::for_path(path, fps).map(Some),
+            None if replay => Ok(Some(Output::Replay(fps))),
+            None => Ok(None),
+        }
+    }
+
+    fn for_path(path: PathBuf, fps: u32) -> Result<Output, RecorderError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gif") => Ok(Output::Gif(path, fps)),
+            Some("cast") => Ok(Output::Cast(path, fps)),
+            _ => Err(RecorderError::UnsupportedFormat(path)),
+        }
+    }
+
+    pub fn write(&self, recorder: &Recorder) -> Result<(), RecorderError> {
+        match self {
+            Output::Gif(path, fps) => {
+                recorder.write_gif(path, *fps)?;
+                println!(
+                    "🎄 Wrote {} frames to \"{}\".",
+                    recorder.frames().len(),
+                    path.display()
+                );
+            }
+            Output::Cast(path, fps) => {
+                recorder.write_cast(BufWriter::new(File::create(path)?), *fps)?;
+                println!(
+                    "🎄 Wrote {} frames to \"{}\".",
+                    recorder.frames().len(),
+                    path.display()
+                );
+            }
+            Output::Replay(fps) => recorder.replay(*fps),
+        }
+        Ok(())
+    }
+}
+
+/// Re-runs `simulate` with an enabled recorder if `--record` or `--replay` was passed on the
+/// command line, and writes the frames to the requested output.
+/// Meant to be called from a solution's `main` after `solve!`, so recording never affects timings.
+pub fn record_from_args(simulate: impl FnOnce(&mut Recorder)) {
+    match Output::from_args() {
+        Ok(Some(output)) => {
+            let mut recorder = Recorder::enabled();
+            simulate(&mut recorder);
+            if let Err(e) = output.write(&recorder) {
+                eprintln!("{}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_recorder_skips_frames() {
+        let mut recorder = Recorder::disabled();
+        recorder.record(|| unreachable!("frame rendered while disabled"));
+        assert!(recorder.frames().is_empty());
+    }
+
+    #[test]
+    fn test_enabled_recorder_collects_frames() {
+        let mut recorder = Recorder::enabled();
+        recorder.record(|| Frame::from("#.\n.#"));
+        recorder.record(|| Frame::from("..\n..\n##"));
+        assert_eq!(recorder.frames().len(), 2);
+        assert_eq!(recorder.dimensions(), (2, 3));
+    }
+
+    #[test]
+    fn test_write_cast() {
+        let mut recorder = Recorder::enabled();
+        recorder.record(|| Frame::from("a\"b"));
+        let mut out = Vec::new();
+        recorder.write_cast(&mut out, 10).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"version\": 2, \"width\": 3, \"height\": 2}\n[0.000000, \"o\", \"\\u001b[2J\\u001b[Ha\\\"b\\r\\n\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_output_for_path() {
+        assert_eq!(
+            Output::for_path("sand.gif".into(), 30).unwrap(),
+            Output::Gif("sand.gif".into(), 30)
+        );
+        assert!(Output::for_path("sand.mp4".into(), 30).is_err());
+    }
+
+    #[test]
+    fn test_output_parse() {
+        let args = |args: &[&str]| {
+            Output::parse(pico_args::Arguments::from_vec(
+                args.iter().map(|arg| arg.into()).collect(),
+            ))
+        };
+        assert_eq!(args(&[]).unwrap(), None);
+        assert_eq!(
+            args(&["--replay", "--fps", "5"]).unwrap(),
+            Some(Output::Replay(5))
+        );
+        assert_eq!(
+            args(&["--record", "sand.cast"]).unwrap(),
+            Some(Output::Cast("sand.cast".into(), DEFAULT_FPS))
+        );
+        assert!(matches!(
+            args(&["--replay", "--fps", "abc"]),
+            Err(RecorderError::InvalidArgument(_))
+        ));
+    }
+}